@@ -0,0 +1,242 @@
+use crate::*;
+
+use core::fmt;
+
+/// Accumulates points and Rects into a bounding Rect
+///
+/// Works with any coordinates, including negative ones, and can be built up incrementally or collected from an iterator
+/// ## Example
+/// ```
+/// # use spatial2d::*;
+/// let bounds: BoundsBuilder<i32> = [IVec2::new(-4, 2), IVec2::new(3, -1)].into_iter().collect();
+///
+/// assert_eq!(
+///     bounds.build(),
+///     Ok(Rect::from_corners(IVec2::new(-4, -1), IVec2::new(3, 2)))
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BoundsBuilder<T: RectScalar> {
+    min: T::V2,
+    max: T::V2,
+    count: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BoundsError {
+    /// No points or Rects were added
+    Empty,
+    /// Every point is on a single horizontal or vertical line, so the bounds have no area
+    Degenerate,
+    /// A point is at the maximum value of the scalar, so the cell bounds can't be represented
+    Overflow,
+}
+
+impl fmt::Display for BoundsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoundsError::Empty => write!(f, "No points were added to the bounds"),
+            BoundsError::Degenerate => {
+                write!(f, "Bounds have zero width or height")
+            }
+            BoundsError::Overflow => write!(
+                f,
+                "Bounds reach the maximum scalar value, so can't be extended to cover the last cell"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BoundsError {}
+
+impl<T: RectScalar> Default for BoundsBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: RectScalar> BoundsBuilder<T> {
+    pub fn new() -> Self {
+        Self {
+            min: T::v2_new(T::max(), T::max()),
+            max: T::v2_new(T::min(), T::min()),
+            count: 0,
+        }
+    }
+
+    /// Number of points added, each Rect counts as 2
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// NaN coordinates are ignored
+    pub fn add_point(&mut self, pos: T::V2) {
+        let (x, y) = (T::v2_x(pos), T::v2_y(pos));
+
+        // NaN is the only value not equal to itself
+        #[allow(clippy::eq_op)]
+        if x != x || y != y {
+            return;
+        }
+
+        if x < T::v2_x(self.min) {
+            *T::v2_x_mut(&mut self.min) = x;
+        }
+        if y < T::v2_y(self.min) {
+            *T::v2_y_mut(&mut self.min) = y;
+        }
+        if x > T::v2_x(self.max) {
+            *T::v2_x_mut(&mut self.max) = x;
+        }
+        if y > T::v2_y(self.max) {
+            *T::v2_y_mut(&mut self.max) = y;
+        }
+
+        self.count += 1;
+    }
+
+    /// Adds both corners, empty Rects are ignored
+    pub fn add_rect(&mut self, rect: &Rect<T>) {
+        if rect.is_empty() {
+            return;
+        }
+
+        self.add_point(rect.top_left);
+        self.add_point(rect.bottom_right);
+    }
+
+    #[must_use]
+    pub fn with_point(mut self, pos: T::V2) -> Self {
+        self.add_point(pos);
+        self
+    }
+
+    #[must_use]
+    pub fn with_rect(mut self, rect: &Rect<T>) -> Self {
+        self.add_rect(rect);
+        self
+    }
+
+    /// The smallest Rect with every point between its corners
+    ///
+    /// A single point, or points along a line, give a Rect with zero width or height.
+    /// With integer grid positions the bottom & right-most cells are outside this Rect, see `build_cells`
+    pub fn build(&self) -> Result<Rect<T>, BoundsError> {
+        if self.is_empty() {
+            return Err(BoundsError::Empty);
+        }
+
+        Ok(Rect {
+            top_left: self.min,
+            bottom_right: self.max,
+        })
+    }
+
+    /// `build`, but errors instead of returning a Rect with zero width or height
+    pub fn build_non_degenerate(&self) -> Result<Rect<T>, BoundsError> {
+        let rect = self.build()?;
+
+        if rect.is_empty() {
+            return Err(BoundsError::Degenerate);
+        }
+
+        Ok(rect)
+    }
+
+    /// The smallest Rect whose `positions()` include every point, treating points as grid cells
+    ///
+    /// `bottom_right` is one past the largest point, so a single point gives a 1x1 Rect
+    pub fn build_cells(&self) -> Result<Rect<T>, BoundsError> {
+        let rect = self.build()?;
+
+        if T::v2_max_element(rect.bottom_right) == T::max() {
+            return Err(BoundsError::Overflow);
+        }
+
+        Ok(Rect {
+            top_left: rect.top_left,
+            bottom_right: rect.bottom_right + T::one(),
+        })
+    }
+}
+
+impl<T: RectScalar> Extend<T::V2> for BoundsBuilder<T> {
+    fn extend<I: IntoIterator<Item = T::V2>>(&mut self, positions: I) {
+        for pos in positions {
+            self.add_point(pos);
+        }
+    }
+}
+
+impl<T: RectScalar> FromIterator<T::V2> for BoundsBuilder<T> {
+    fn from_iter<I: IntoIterator<Item = T::V2>>(positions: I) -> Self {
+        let mut bounds = Self::new();
+        bounds.extend(positions);
+        bounds
+    }
+}
+
+impl<T: RectScalar> Rect<T> {
+    /// Bounds of `positions`, see `BoundsBuilder::build`
+    #[inline]
+    pub fn bounds_of(positions: impl IntoIterator<Item = T::V2>) -> Result<Self, BoundsError> {
+        positions.into_iter().collect::<BoundsBuilder<T>>().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds_builder() {
+        let points = [IVec2::new(-5, -8), IVec2::new(-2, -3), IVec2::new(-4, -6)];
+
+        assert_eq!(
+            Rect::<i32>::bounds_of(points),
+            Ok(Rect::from_corners(IVec2::new(-5, -8), IVec2::new(-2, -3)))
+        );
+        assert_eq!(
+            BoundsBuilder::<i32>::from_iter(points).build_cells(),
+            Ok(Rect::from_corners(IVec2::new(-5, -8), IVec2::new(-1, -2)))
+        );
+
+        assert_eq!(BoundsBuilder::<u32>::new().build(), Err(BoundsError::Empty));
+
+        let single = BoundsBuilder::<u32>::new().with_point(UVec2::new(3, 4));
+        assert_eq!(single.build().map(|rect| rect.area()), Ok(0));
+        assert_eq!(single.build_non_degenerate(), Err(BoundsError::Degenerate));
+        assert_eq!(
+            single.build_cells(),
+            Ok(Rect::from_corners(UVec2::new(3, 4), UVec2::new(4, 5)))
+        );
+
+        let mut rects = BoundsBuilder::<f32>::new()
+            .with_rect(&Rect::from_corners(Vec2::ZERO, Vec2::ONE))
+            .with_rect(&Rect::from_corners(Vec2::splat(4.0), Vec2::splat(4.0)));
+        rects.add_point(Vec2::new(f32::NAN, 10.0));
+        rects.extend([Vec2::new(-1.0, 2.0)]);
+
+        assert_eq!(rects.len(), 3);
+        assert_eq!(
+            rects.build(),
+            Ok(Rect::from_corners(
+                Vec2::new(-1.0, 0.0),
+                Vec2::new(1.0, 2.0)
+            ))
+        );
+
+        assert_eq!(
+            BoundsBuilder::<u16>::new()
+                .with_point(U16Vec2::new(0, u16::MAX))
+                .build_cells(),
+            Err(BoundsError::Overflow)
+        );
+    }
+}
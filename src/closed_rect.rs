@@ -0,0 +1,146 @@
+use crate::*;
+
+/// A Rect of grid cells where both corners are inside, `bottom_right` is the last cell
+///
+/// The closed counterpart to the half-open `Rect`, which treats `bottom_right` as outside.
+/// A ClosedRect always contains at least one cell, so converting from an empty `Rect` fails.
+/// Converting between the two shifts `bottom_right` by one, so both directions are lossless.
+/// Only integer scalars are supported, as a closed range of floats has no "last cell"
+#[cfg_attr(feature = "bevy", derive(Reflect))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ClosedRect<T: IntegerScalar> {
+    pub top_left: T::V2,
+    /// Inclusive
+    pub bottom_right: T::V2,
+}
+
+impl<T: IntegerScalar> ClosedRect<T> {
+    /// Panics if `bottom_right` is above or left of `top_left`, equal corners are a single cell
+    pub fn from_corners(top_left: T::V2, bottom_right: T::V2) -> Self {
+        assert!(
+            T::v2_x(top_left) <= T::v2_x(bottom_right)
+                && T::v2_y(top_left) <= T::v2_y(bottom_right),
+            "{top_left}, {bottom_right}"
+        );
+
+        Self {
+            top_left,
+            bottom_right,
+        }
+    }
+
+    /// Panics if either side of `size` is zero
+    pub fn from_top_left_and_size(top_left: T::V2, size: T::V2) -> Self {
+        assert!(
+            T::v2_min_element(size) > T::zero(),
+            "ClosedRect size must be at least 1x1, got {size}"
+        );
+
+        Self {
+            top_left,
+            bottom_right: top_left + size - T::one(),
+        }
+    }
+
+    /// Half-open equivalent, `bottom_right` moves one past the last cell
+    #[inline]
+    pub fn to_half_open(&self) -> Rect<T> {
+        Rect {
+            top_left: self.top_left,
+            bottom_right: self.bottom_right + T::one(),
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> T {
+        T::v2_x(self.bottom_right) - T::v2_x(self.top_left) + T::one()
+    }
+
+    #[inline]
+    pub fn height(&self) -> T {
+        T::v2_y(self.bottom_right) - T::v2_y(self.top_left) + T::one()
+    }
+
+    #[inline]
+    pub fn size(&self) -> T::V2 {
+        self.bottom_right - self.top_left + T::one()
+    }
+
+    #[inline]
+    pub fn area(&self) -> T {
+        T::v2_element_product(self.size())
+    }
+
+    /// Inclusive of `bottom_right`
+    #[inline]
+    pub fn contains_pos(&self, pos: T::V2) -> bool {
+        T::v2_x(pos) >= T::v2_x(self.top_left)
+            && T::v2_y(pos) >= T::v2_y(self.top_left)
+            && T::v2_x(pos) <= T::v2_x(self.bottom_right)
+            && T::v2_y(pos) <= T::v2_y(self.bottom_right)
+    }
+}
+
+impl<T: IntegerScalar> From<ClosedRect<T>> for Rect<T> {
+    #[inline]
+    fn from(rect: ClosedRect<T>) -> Self {
+        rect.to_half_open()
+    }
+}
+
+impl<T: IntegerScalar> TryFrom<Rect<T>> for ClosedRect<T> {
+    type Error = RectError;
+
+    #[inline]
+    fn try_from(rect: Rect<T>) -> Result<Self, Self::Error> {
+        rect.to_closed().ok_or(RectError::Empty)
+    }
+}
+
+impl ClosedRect<u32> {
+    /// Every cell, including the last row and column
+    #[inline]
+    pub fn positions(&self) -> Vec<UVec2> {
+        self.iter_positions().collect()
+    }
+
+    /// Cells on the outermost row and column on each side
+    #[inline]
+    pub fn border_positions(&self) -> Vec<UVec2> {
+        self.iter_border_positions().collect()
+    }
+
+    #[inline]
+    pub fn iter_positions(&self) -> GridPositions<UVec2> {
+        self.to_half_open().iter_positions()
+    }
+
+    #[inline]
+    pub fn iter_border_positions(&self) -> BorderPositions<UVec2> {
+        self.to_half_open().iter_border_positions()
+    }
+}
+
+impl ClosedRect<i32> {
+    /// Every cell, including the last row and column
+    #[inline]
+    pub fn positions(&self) -> Vec<IVec2> {
+        self.iter_positions().collect()
+    }
+
+    /// Cells on the outermost row and column on each side
+    #[inline]
+    pub fn border_positions(&self) -> Vec<IVec2> {
+        self.iter_border_positions().collect()
+    }
+
+    #[inline]
+    pub fn iter_positions(&self) -> GridPositions<IVec2> {
+        self.to_half_open().iter_positions()
+    }
+
+    #[inline]
+    pub fn iter_border_positions(&self) -> BorderPositions<IVec2> {
+        self.to_half_open().iter_border_positions()
+    }
+}
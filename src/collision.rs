@@ -0,0 +1,409 @@
+use crate::*;
+
+/// Where a moving Rect first touches another, from `Rect::sweep`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SweepHit {
+    /// Fraction of the velocity moved before touching, 0..=1
+    pub time: f32,
+    /// Faces away from the surface that was hit, back towards the moving Rect.
+    /// eg. moving right into a wall gives `W`, landing on a floor gives `N`
+    pub normal: Dir,
+}
+
+/// Where a ray enters a Rect, from `Rect::ray_cast`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit {
+    /// Multiple of the ray direction travelled, the hit point is `origin + direction * time`
+    pub time: f32,
+    pub point: Vec2,
+    /// The face the ray entered through, pointing out of the Rect
+    pub normal: Dir,
+}
+
+/// Result of `Matrix<bool>::move_and_slide`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SlideResult {
+    pub rect: Rect<f32>,
+    /// The velocity with any blocked axis zeroed
+    pub velocity: Vec2,
+    /// Normal of the wall that blocked horizontal movement
+    pub collision_x: Option<Dir>,
+    /// Normal of the floor or ceiling that blocked vertical movement
+    pub collision_y: Option<Dir>,
+}
+
+impl SlideResult {
+    /// Landed on something while moving down
+    #[inline]
+    pub fn is_on_floor(&self) -> bool {
+        self.collision_y == Some(Dir::N)
+    }
+
+    #[inline]
+    pub fn is_on_ceiling(&self) -> bool {
+        self.collision_y == Some(Dir::S)
+    }
+
+    #[inline]
+    pub fn is_on_wall(&self) -> bool {
+        self.collision_x.is_some()
+    }
+}
+
+/// Collision, y points down so `N` is up
+///
+/// Faces are closed here, so a ray along an edge hits, but Rects that only touch don't overlap (matching `overlaps`)
+impl Rect<f32> {
+    /// Swept AABB, the first time Self touches `other` while moving by `velocity`
+    ///
+    /// None if they never touch during this move, only slide along each other, or already overlap
+    pub fn sweep(&self, velocity: Vec2, other: &Rect<f32>) -> Option<SweepHit> {
+        let (entry_x, exit_x) = sweep_axis(
+            self.top_left.x,
+            self.bottom_right.x,
+            other.top_left.x,
+            other.bottom_right.x,
+            velocity.x,
+        )?;
+        let (entry_y, exit_y) = sweep_axis(
+            self.top_left.y,
+            self.bottom_right.y,
+            other.top_left.y,
+            other.bottom_right.y,
+            velocity.y,
+        )?;
+
+        let entry = entry_x.max(entry_y);
+        let exit = exit_x.min(exit_y);
+
+        if entry >= exit || !(0.0..=1.0).contains(&entry) {
+            return None;
+        }
+
+        let normal = if entry_x > entry_y {
+            if velocity.x > 0.0 { Dir::W } else { Dir::E }
+        } else if velocity.y > 0.0 {
+            Dir::N
+        } else {
+            Dir::S
+        };
+
+        Some(SweepHit {
+            time: entry,
+            normal,
+        })
+    }
+
+    /// Slab test, where a ray from `origin` first enters Self, within `max_time` multiples of `direction`
+    ///
+    /// Rays starting inside Self don't hit, see `intersects_segment`
+    pub fn ray_cast(&self, origin: Vec2, direction: Vec2, max_time: f32) -> Option<RayHit> {
+        let (origin_64, direction_64) = (origin.as_dvec2(), direction.as_dvec2());
+        let (min, max) = (self.top_left.as_dvec2(), self.bottom_right.as_dvec2());
+
+        let (entry_x, exit_x) = slab(origin_64.x, direction_64.x, min.x, max.x)?;
+        let (entry_y, exit_y) = slab(origin_64.y, direction_64.y, min.y, max.y)?;
+
+        let entry = entry_x.max(entry_y) as f32;
+        let exit = exit_x.min(exit_y) as f32;
+
+        if entry > exit || entry < 0.0 || entry > max_time {
+            return None;
+        }
+
+        let normal = if entry_x > entry_y {
+            if direction.x > 0.0 { Dir::W } else { Dir::E }
+        } else if direction.y > 0.0 {
+            Dir::N
+        } else {
+            Dir::S
+        };
+
+        Some(RayHit {
+            time: entry,
+            point: origin + direction * entry,
+            normal,
+        })
+    }
+
+    /// True if any part of the segment from `start` to `end` is inside Self, including when `start` is
+    pub fn intersects_segment(&self, start: Vec2, end: Vec2) -> bool {
+        let start = start.as_dvec2();
+
+        slab_interval(
+            start,
+            end.as_dvec2() - start,
+            self.top_left.as_dvec2(),
+            self.bottom_right.as_dvec2(),
+        )
+        .is_some_and(|(entry, exit)| entry <= 1.0 && exit >= 0.0)
+    }
+}
+
+/// Entry and exit times of a moving span `min..max` against `other_min..other_max`
+///
+/// None if a stationary axis never overlaps
+#[inline]
+fn sweep_axis(
+    min: f32,
+    max: f32,
+    other_min: f32,
+    other_max: f32,
+    velocity: f32,
+) -> Option<(f32, f32)> {
+    if velocity > 0.0 {
+        Some(((other_min - max) / velocity, (other_max - min) / velocity))
+    } else if velocity < 0.0 {
+        Some(((other_max - min) / velocity, (other_min - max) / velocity))
+    } else if min < other_max && other_min < max {
+        Some((f32::NEG_INFINITY, f32::INFINITY))
+    } else {
+        None
+    }
+}
+
+/// Entry and exit times of a ray through the slab `min..=max`
+///
+/// None if the ray is parallel to and outside the slab.
+/// Entry times can be negative, callers decide what a ray starting inside means:
+/// `Rect::ray_cast` treats it as a miss, while `Ray2::intersect_rect` hits at time 0
+#[inline]
+fn slab(origin: f64, direction: f64, min: f64, max: f64) -> Option<(f64, f64)> {
+    if direction == 0.0 {
+        return (min..=max)
+            .contains(&origin)
+            .then_some((f64::NEG_INFINITY, f64::INFINITY));
+    }
+
+    let t1 = (min - origin) / direction;
+    let t2 = (max - origin) / direction;
+
+    Some((t1.min(t2), t1.max(t2)))
+}
+
+/// Range of times `origin + direction * t` spends between `min` and `max`, both axes of `slab` combined
+///
+/// The range is unbounded for a zero `direction`, as long as `origin` is inside
+#[inline]
+pub(crate) fn slab_interval(
+    origin: DVec2,
+    direction: DVec2,
+    min: DVec2,
+    max: DVec2,
+) -> Option<(f64, f64)> {
+    let (entry_x, exit_x) = slab(origin.x, direction.x, min.x, max.x)?;
+    let (entry_y, exit_y) = slab(origin.y, direction.y, min.y, max.y)?;
+
+    let entry = entry_x.max(entry_y);
+    let exit = exit_x.min(exit_y);
+
+    (entry <= exit).then_some((entry, exit))
+}
+
+impl Matrix<bool> {
+    /// Moves `rect` by `velocity`, stopping against `true` (solid) cells, first along x then along y
+    ///
+    /// Each cell covers `pos..pos + 1`, and cells outside the matrix are solid.
+    /// The whole path of each axis is checked so fast moves can't tunnel through walls.
+    /// Cells `rect` already overlaps are ignored, so it can move out of them
+    pub fn move_and_slide(&self, rect: Rect<f32>, velocity: Vec2) -> SlideResult {
+        let mut result = SlideResult {
+            rect,
+            velocity,
+            collision_x: None,
+            collision_y: None,
+        };
+
+        for horizontal in [true, false] {
+            let (amount, step) = if horizontal {
+                (velocity.x, Vec2::new(velocity.x, 0.0))
+            } else {
+                (velocity.y, Vec2::new(0.0, velocity.y))
+            };
+
+            if amount == 0.0 {
+                continue;
+            }
+
+            let start = result.rect;
+            let moved = Rect::from_top_left_and_size(start.top_left + step, start.size());
+
+            // The nearest blocking cell edge along the path
+            let blocking = self
+                .solid_cells_in(start.union(&moved))
+                .filter(|cell| !cell.overlaps(&start))
+                .map(|cell| match (horizontal, amount > 0.0) {
+                    (true, true) => cell.top_left.x,
+                    (true, false) => cell.bottom_right.x,
+                    (false, true) => cell.top_left.y,
+                    (false, false) => cell.bottom_right.y,
+                })
+                .reduce(|a, b| if amount > 0.0 { a.min(b) } else { a.max(b) });
+
+            let Some(edge) = blocking else {
+                result.rect = moved;
+                continue;
+            };
+
+            // Built from the edge itself so the Rect ends exactly on it, rather than wherever `top_left + size` rounds to
+            let mut snapped = start;
+            let size = start.size();
+
+            let normal = match (horizontal, amount > 0.0) {
+                (true, true) => {
+                    snapped.bottom_right.x = edge;
+                    snapped.top_left.x = edge - size.x;
+                    Dir::W
+                }
+                (true, false) => {
+                    snapped.top_left.x = edge;
+                    snapped.bottom_right.x = edge + size.x;
+                    Dir::E
+                }
+                (false, true) => {
+                    snapped.bottom_right.y = edge;
+                    snapped.top_left.y = edge - size.y;
+                    Dir::N
+                }
+                (false, false) => {
+                    snapped.top_left.y = edge;
+                    snapped.bottom_right.y = edge + size.y;
+                    Dir::S
+                }
+            };
+
+            result.rect = snapped;
+
+            if horizontal {
+                result.velocity.x = 0.0;
+                result.collision_x = Some(normal);
+            } else {
+                result.velocity.y = 0.0;
+                result.collision_y = Some(normal);
+            }
+        }
+
+        result
+    }
+
+    /// Solid cells overlapping `area`, as unit Rects
+    fn solid_cells_in(&self, area: Rect<f32>) -> impl Iterator<Item = Rect<f32>> + '_ {
+        let min = area.top_left.floor().as_ivec2();
+        let max = area.bottom_right.ceil().as_ivec2();
+        let size = self.size().as_ivec2();
+
+        Rect::<i32>::from_corners(min, max.max(min))
+            .iter_positions()
+            .filter(move |&pos| {
+                pos.cmplt(IVec2::ZERO).any() || pos.cmpge(size).any() || *self.get(pos.as_uvec2())
+            })
+            .map(|pos| Rect::from_top_left_and_size(pos.as_vec2(), Vec2::ONE))
+            .filter(move |cell| cell.overlaps(&area))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_and_ray_cast() {
+        let mover = Rect::<f32>::from_corners(Vec2::ZERO, Vec2::splat(2.0));
+        let wall = Rect::<f32>::from_corners(Vec2::new(5.0, -1.0), Vec2::new(6.0, 4.0));
+
+        let hit = mover.sweep(Vec2::new(6.0, 0.0), &wall).unwrap();
+        assert_eq!(hit.time, 0.5);
+        assert_eq!(hit.normal, Dir::W);
+
+        assert_eq!(mover.sweep(Vec2::new(2.0, 0.0), &wall), None);
+        assert_eq!(mover.sweep(Vec2::new(0.0, 6.0), &wall), None);
+
+        let floor = Rect::<f32>::from_corners(Vec2::new(-5.0, 2.0), Vec2::new(5.0, 3.0));
+        // Sliding along the floor isn't a hit, falling onto it is
+        assert_eq!(mover.sweep(Vec2::new(3.0, 0.0), &floor), None);
+        assert_eq!(
+            mover.sweep(Vec2::new(1.0, 1.0), &floor).unwrap().normal,
+            Dir::N
+        );
+
+        let ray = wall
+            .ray_cast(Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0), 100.0)
+            .unwrap();
+        assert_eq!(ray.time, 5.0);
+        assert_eq!(ray.point, Vec2::new(5.0, 1.0));
+        assert_eq!(ray.normal, Dir::W);
+
+        assert_eq!(
+            wall.ray_cast(Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0), 4.0),
+            None
+        );
+        assert_eq!(
+            wall.ray_cast(Vec2::new(0.0, 1.0), Vec2::new(-1.0, 0.0), 100.0),
+            None
+        );
+        assert_eq!(
+            wall.ray_cast(Vec2::new(0.0, 5.0), Vec2::new(1.0, 0.0), 100.0),
+            None
+        );
+
+        assert!(wall.intersects_segment(Vec2::new(5.5, 0.0), Vec2::new(20.0, 0.0)));
+        assert!(wall.intersects_segment(Vec2::new(4.0, 5.0), Vec2::new(7.0, -2.0)));
+        assert!(!wall.intersects_segment(Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0)));
+    }
+
+    #[test]
+    fn test_move_and_slide() {
+        // A floor along the bottom row and a wall on the right
+        let mut tiles = Matrix::splat(UVec2::new(10, 8), false);
+
+        for x in 0..10 {
+            tiles.set(UVec2::new(x, 7), true);
+        }
+        for y in 0..8 {
+            tiles.set(UVec2::new(8, y), true);
+        }
+
+        let player = Rect::<f32>::from_top_left_and_size(Vec2::new(2.0, 3.5), Vec2::new(1.0, 2.0));
+
+        let fall = tiles.move_and_slide(player, Vec2::new(0.5, 4.0));
+        assert!(fall.is_on_floor());
+        assert_eq!(fall.rect.bottom_right.y, 7.0);
+        assert_eq!(fall.rect.top_left.x, 2.5);
+        assert_eq!(fall.velocity, Vec2::new(0.5, 0.0));
+
+        // Fast enough to tunnel through the wall if only the end position was checked
+        let dash = tiles.move_and_slide(fall.rect, Vec2::new(20.0, 0.0));
+        assert!(dash.is_on_wall());
+        assert_eq!(dash.collision_x, Some(Dir::W));
+        assert_eq!(dash.rect.bottom_right.x, 8.0);
+
+        // The top of the matrix counts as solid
+        let jump = tiles.move_and_slide(dash.rect, Vec2::new(0.0, -20.0));
+        assert!(jump.is_on_ceiling());
+        assert_eq!(jump.rect.top_left.y, 0.0);
+    }
+
+    #[test]
+    fn test_move_and_slide_resting() {
+        let mut tiles = Matrix::splat(UVec2::new(10, 8), false);
+
+        for x in 0..10 {
+            tiles.set(UVec2::new(x, 7), true);
+        }
+        for y in 0..8 {
+            tiles.set(UVec2::new(8, y), true);
+        }
+
+        // 0.3 isn't exact in f32, resting against the wall and floor must not let it sink into them
+        let mut body = Rect::<f32>::from_top_left_and_size(Vec2::new(6.35, 5.15), Vec2::splat(0.3));
+
+        for _ in 0..3 {
+            let result = tiles.move_and_slide(body, Vec2::new(2.0, 2.0));
+
+            assert!(result.is_on_wall() && result.is_on_floor());
+            assert_eq!(result.rect.bottom_right, Vec2::new(8.0, 7.0));
+
+            body = result.rect;
+        }
+    }
+}
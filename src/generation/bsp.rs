@@ -0,0 +1,312 @@
+use crate::*;
+
+use core::ops::Range;
+
+/// Settings for `BspTree::generate`
+#[derive(Clone, Debug, PartialEq)]
+pub struct BspConfig {
+    /// Leaves are never split into anything smaller than this
+    pub min_leaf_size: UVec2,
+    /// Maximum number of times the bounds are recursively split
+    pub max_depth: u32,
+    /// Leaves with an `aspect_ratio` below this are always split across their longest side
+    pub min_aspect_ratio: f32,
+    /// Range of length percentages each split is chosen from
+    pub split_range: Range<f32>,
+    /// Minimum gap between a room and the edge of its leaf
+    pub room_padding: u32,
+    pub min_room_size: UVec2,
+}
+
+impl Default for BspConfig {
+    fn default() -> Self {
+        Self {
+            min_leaf_size: UVec2::splat(8),
+            max_depth: 6,
+            min_aspect_ratio: 0.5,
+            split_range: 0.35..0.65,
+            room_padding: 1,
+            min_room_size: UVec2::splat(3),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BspNode {
+    pub rect: Rect<u32>,
+    /// Indices of the two halves, None for leaves
+    pub children: Option<[usize; 2]>,
+    /// Only leaves contain rooms
+    pub room: Option<Rect<u32>>,
+}
+
+/// Binary space partition dungeon
+///
+/// The bounds are recursively bisected, each leaf gets a room,
+/// and the rooms of sibling subtrees are connected with corridors so every room is reachable
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BspTree {
+    /// Index 0 is the root
+    pub nodes: Vec<BspNode>,
+    pub corridors: Vec<Corridor>,
+}
+
+impl BspTree {
+    pub fn generate(bounds: Rect<u32>, config: &BspConfig, rng: &mut Rng) -> Self {
+        let mut tree = Self {
+            nodes: vec![BspNode {
+                rect: bounds,
+                children: None,
+                room: None,
+            }],
+            corridors: Vec::new(),
+        };
+
+        tree.split(0, 0, config, rng);
+        tree.connect(0, rng);
+
+        tree
+    }
+
+    /// Recursively splits a node, then carves a room in each leaf
+    fn split(&mut self, node_idx: usize, depth: u32, config: &BspConfig, rng: &mut Rng) {
+        let rect = self.nodes[node_idx].rect;
+
+        let halves = if depth < config.max_depth {
+            Self::choose_split(rect, config, rng)
+        } else {
+            None
+        };
+
+        let Some((a, b)) = halves else {
+            self.nodes[node_idx].room = Self::carve_room(rect, config, rng);
+
+            return;
+        };
+
+        let a_idx = self.nodes.len();
+        let b_idx = a_idx + 1;
+
+        for half in [a, b] {
+            self.nodes.push(BspNode {
+                rect: half,
+                children: None,
+                room: None,
+            });
+        }
+
+        self.nodes[node_idx].children = Some([a_idx, b_idx]);
+
+        self.split(a_idx, depth + 1, config, rng);
+        self.split(b_idx, depth + 1, config, rng);
+    }
+
+    /// Returns None if `rect` can't be split without breaking the size constraints
+    fn choose_split(
+        rect: Rect<u32>,
+        config: &BspConfig,
+        rng: &mut Rng,
+    ) -> Option<(Rect<u32>, Rect<u32>)> {
+        let can_split_vertically = rect.width() >= config.min_leaf_size.x * 2;
+        let can_split_horizontally = rect.height() >= config.min_leaf_size.y * 2;
+
+        let orientation = match (can_split_vertically, can_split_horizontally) {
+            (false, false) => return None,
+            (true, false) => Orientation::Vertical,
+            (false, true) => Orientation::Horizontal,
+            (true, true) => {
+                if rect.width() != rect.height() && rect.aspect_ratio() < config.min_aspect_ratio {
+                    // Too thin, so cut across the longest side
+                    rect.longest_orientation().opposite()
+                } else if rng.gen_range(0..2) == 0 {
+                    Orientation::Vertical
+                } else {
+                    Orientation::Horizontal
+                }
+            }
+        };
+
+        let (length, min_length) = match orientation {
+            Orientation::Vertical => (rect.width(), config.min_leaf_size.x),
+            _ => (rect.height(), config.min_leaf_size.y),
+        };
+
+        let percent = if config.split_range.is_empty() {
+            config.split_range.start
+        } else {
+            rng.gen_range(config.split_range.clone())
+        };
+
+        let split_point = rect
+            .get_local_coord_at_length_percent(orientation.opposite(), percent)
+            .ok()?
+            .clamp(min_length, length - min_length);
+
+        rect.bisect_at(orientation, split_point).ok()
+    }
+
+    fn carve_room(leaf: Rect<u32>, config: &BspConfig, rng: &mut Rng) -> Option<Rect<u32>> {
+        let padding = UVec2::splat(config.room_padding);
+        let available = leaf.size().saturating_sub(padding * 2);
+
+        if available.x < config.min_room_size.x || available.y < config.min_room_size.y {
+            return None;
+        }
+
+        let size = UVec2::new(
+            rng.gen_range(config.min_room_size.x..available.x + 1),
+            rng.gen_range(config.min_room_size.y..available.y + 1),
+        );
+        let slack = available - size;
+        let offset = UVec2::new(rng.gen_range(0..slack.x + 1), rng.gen_range(0..slack.y + 1));
+
+        Some(Rect::from_top_left_and_size(
+            leaf.top_left + padding + offset,
+            size,
+        ))
+    }
+
+    /// Connects the rooms of each pair of siblings, bottom up
+    fn connect(&mut self, node_idx: usize, rng: &mut Rng) {
+        let Some([a_idx, b_idx]) = self.nodes[node_idx].children else {
+            return;
+        };
+
+        self.connect(a_idx, rng);
+        self.connect(b_idx, rng);
+
+        let rooms_a = self.subtree_rooms(a_idx);
+        let rooms_b = self.subtree_rooms(b_idx);
+
+        // Connect the closest pair, so corridors don't cross the whole map
+        let closest = rooms_a
+            .iter()
+            .cartesian_product(rooms_b.iter())
+            .min_by_key(|(a, b)| a.center().distance_manhattan(b.center()));
+
+        if let Some((a, b)) = closest {
+            self.corridors
+                .push(Corridor::random(a.center(), b.center(), rng));
+        }
+    }
+
+    fn subtree_rooms(&self, node_idx: usize) -> Vec<Rect<u32>> {
+        let mut rooms = Vec::new();
+        let mut stack = vec![node_idx];
+
+        while let Some(idx) = stack.pop() {
+            let node = &self.nodes[idx];
+
+            match node.children {
+                Some(children) => stack.extend(children),
+                None => rooms.extend(node.room),
+            }
+        }
+
+        rooms
+    }
+
+    #[inline]
+    pub fn root(&self) -> &BspNode {
+        &self.nodes[0]
+    }
+
+    pub fn leaves(&self) -> impl Iterator<Item = &BspNode> {
+        self.nodes.iter().filter(|node| node.children.is_none())
+    }
+
+    pub fn rooms(&self) -> Vec<Rect<u32>> {
+        self.leaves().filter_map(|node| node.room).collect()
+    }
+
+    /// Renders rooms and corridors as `true` cells
+    ///
+    /// The matrix is sized to fit the root bounds, so positions match the Rects
+    pub fn to_matrix(&self) -> Matrix<bool> {
+        let mut matrix = Matrix::splat(self.root().rect.bottom_right, false);
+
+        for room in self.rooms() {
+            for pos in room.positions() {
+                matrix.set(pos, true);
+            }
+        }
+
+        for corridor in &self.corridors {
+            matrix.carve_corridor(corridor, 1, true);
+        }
+
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bisect_at() {
+        let rect: Rect<u32> = Rect::from_corners(UVec2::new(2, 2), UVec2::new(10, 6));
+
+        let (left, right) = rect.bisect_at(Orientation::Vertical, 3).unwrap();
+        assert_eq!(left, Rect::from_corners(UVec2::new(2, 2), UVec2::new(5, 6)));
+        assert_eq!(
+            right,
+            Rect::from_corners(UVec2::new(5, 2), UVec2::new(10, 6))
+        );
+
+        let (top, bottom) = rect
+            .bisect_at_length_percent(Orientation::Horizontal, 0.5)
+            .unwrap();
+        assert_eq!(top, Rect::from_corners(UVec2::new(2, 2), UVec2::new(10, 4)));
+        assert_eq!(
+            bottom,
+            Rect::from_corners(UVec2::new(2, 4), UVec2::new(10, 6))
+        );
+
+        assert_eq!(
+            rect.bisect_at(Orientation::DiagNE, 1),
+            Err(RectError::DiagonalOrientation(Orientation::DiagNE))
+        );
+        assert_eq!(
+            rect.bisect_at_length_percent(Orientation::DiagNE, 0.5),
+            Err(RectError::DiagonalOrientation(Orientation::DiagNE))
+        );
+        assert_eq!(
+            rect.bisect_at(Orientation::Horizontal, 4),
+            Err(RectError::SplitOutOfBounds)
+        );
+        assert_eq!(
+            rect.get_global_coord_at_length_percent(Orientation::Horizontal, 0.5),
+            Ok(6)
+        );
+    }
+
+    #[test]
+    fn test_bsp_generate() {
+        let bounds = Rect::from_corners(UVec2::ZERO, UVec2::new(80, 50));
+        let config = BspConfig::default();
+
+        for seed in 0..8 {
+            let tree = BspTree::generate(bounds, &config, &mut Rng::with_seed(seed));
+            let rooms = tree.rooms();
+
+            assert!(rooms.len() > 1, "seed {seed}");
+
+            for leaf in tree.leaves() {
+                assert!(leaf.rect.width() >= config.min_leaf_size.x);
+                assert!(leaf.rect.height() >= config.min_leaf_size.y);
+
+                if let Some(room) = leaf.room {
+                    assert!(leaf.rect.contains_rect(&room));
+                }
+            }
+
+            // Every room is reachable from every other room
+            let (_, region_count) = tree
+                .to_matrix()
+                .label_regions(|&v, _| v, Connectivity::Four);
+
+            assert_eq!(region_count, 1, "seed {seed}");
+        }
+    }
+}
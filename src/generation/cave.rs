@@ -0,0 +1,255 @@
+use crate::*;
+
+use std::collections::VecDeque;
+
+/// Classic "random fill + smoothing" cellular automaton cave generator
+///
+/// Output cells are `true` for open floor and `false` for wall
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaveGenerator {
+    /// Chance of each cell starting as a wall
+    pub fill_probability: f32,
+    /// Number of smoothing steps
+    pub iterations: u32,
+    /// A floor cell becomes a wall when it has at least this many wall neighbours
+    pub birth_limit: u8,
+    /// A wall stays a wall when it has at least this many wall neighbours
+    pub survival_limit: u8,
+    /// `Eight` = Moore neighbourhood, `Four` = von Neumann neighbourhood
+    pub neighbourhood: Connectivity,
+    /// Counts cells outside the matrix as walls while smoothing
+    pub border_is_wall: bool,
+    /// Floor regions with fewer cells than this are filled in
+    pub min_region_size: u32,
+    /// Carves tunnels between the remaining regions so every floor cell is reachable
+    pub connect_regions: bool,
+}
+
+impl Default for CaveGenerator {
+    fn default() -> Self {
+        Self {
+            fill_probability: 0.45,
+            iterations: 5,
+            birth_limit: 5,
+            survival_limit: 4,
+            neighbourhood: Connectivity::Eight,
+            border_is_wall: true,
+            min_region_size: 16,
+            connect_regions: true,
+        }
+    }
+}
+
+impl CaveGenerator {
+    pub fn generate(&self, size: UVec2, rng: &mut Rng) -> Matrix<bool> {
+        let cells = (0..size.element_product())
+            .map(|_| rng.gen_range(0.0..1.0) >= self.fill_probability)
+            .collect();
+
+        let mut cave = Matrix::from_elements(cells, size);
+
+        self.smooth(&mut cave);
+        self.remove_small_regions(&mut cave);
+
+        if self.connect_regions {
+            connect_regions(&mut cave);
+        }
+
+        cave
+    }
+
+    /// Runs `iterations` smoothing steps, swapping between two buffers
+    pub fn smooth(&self, cave: &mut Matrix<bool>) {
+        let size = cave.size();
+        let offsets: &[IVec2] = match self.neighbourhood {
+            Connectivity::Four => &Dir::ALL_VECTORS_CARDINAL,
+            Connectivity::Eight => &Dir::ALL_VECTORS,
+        };
+
+        let positions = size.positions();
+        let mut next = cave.clone();
+
+        for _ in 0..self.iterations {
+            for &pos in &positions {
+                let walls = offsets
+                    .iter()
+                    .filter(|&&offset| {
+                        let neighbour = pos.as_ivec2() + offset;
+
+                        if neighbour.min_element() < 0
+                            || !Matrix::<bool>::is_in_bounds_multi(neighbour.as_uvec2(), size)
+                        {
+                            self.border_is_wall
+                        } else {
+                            !*cave.get(neighbour.as_uvec2())
+                        }
+                    })
+                    .count() as u8;
+
+                let is_wall = !*cave.get(pos);
+                let limit = if is_wall {
+                    self.survival_limit
+                } else {
+                    self.birth_limit
+                };
+
+                next.set(pos, walls < limit);
+            }
+
+            core::mem::swap(cave, &mut next);
+        }
+    }
+
+    /// Fills in floor regions smaller than `min_region_size`
+    pub fn remove_small_regions(&self, cave: &mut Matrix<bool>) {
+        let (labels, region_count) = cave.label_regions(|&v, _| v, Connectivity::Four);
+
+        let mut cell_counts = vec![0_u32; region_count as usize + 1];
+
+        for &label in labels.iter() {
+            cell_counts[label as usize] += 1;
+        }
+
+        for (&label, pos) in labels.iter_with_pos() {
+            if label != 0 && cell_counts[label as usize] < self.min_region_size {
+                cave.set(pos, false);
+            }
+        }
+    }
+}
+
+/// Carves the shortest tunnels needed to join every floor region, starting from the largest
+fn connect_regions(cave: &mut Matrix<bool>) {
+    let size = cave.size();
+    let (labels, region_count) = cave.label_regions(|&v, _| v, Connectivity::Four);
+
+    if region_count < 2 {
+        return;
+    }
+
+    let mut cell_counts = vec![0_u32; region_count as usize + 1];
+
+    for &label in labels.iter() {
+        cell_counts[label as usize] += 1;
+    }
+
+    let largest = (1..=region_count)
+        .max_by_key(|&label| cell_counts[label as usize])
+        .unwrap();
+
+    let mut connected = BitMatrix::new(size);
+    let connect_label = |label: u32, connected: &mut BitMatrix| {
+        for (&l, pos) in labels.iter_with_pos() {
+            if l == label {
+                connected.set(pos, true);
+            }
+        }
+    };
+
+    connect_label(largest, &mut connected);
+
+    for _ in 1..region_count {
+        // Breadth first search outwards from everything connected so far
+        let mut parents: Matrix<Option<UVec2>> = Matrix::splat(size, None);
+        let mut visited = connected.clone();
+        let mut queue: VecDeque<UVec2> = size
+            .positions()
+            .into_iter()
+            .filter(|&p| connected.get(p))
+            .collect();
+
+        let mut reached = None;
+
+        'search: while let Some(pos) = queue.pop_front() {
+            for neighbour in cave.neighbours_no_diag(pos) {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+
+                parents.set(neighbour, Some(pos));
+
+                if *labels.get(neighbour) != 0 {
+                    reached = Some(neighbour);
+
+                    break 'search;
+                }
+
+                queue.push_back(neighbour);
+            }
+        }
+
+        let Some(reached) = reached else {
+            return;
+        };
+
+        // Carve back along the path to the connected area
+        let mut current = *parents.get(reached);
+
+        while let Some(pos) = current {
+            if connected.get(pos) {
+                break;
+            }
+
+            cave.set(pos, true);
+            connected.set(pos, true);
+            current = *parents.get(pos);
+        }
+
+        connect_label(*labels.get(reached), &mut connected);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cave_connected() {
+        for seed in 0..8 {
+            let cave =
+                CaveGenerator::default().generate(UVec2::new(60, 40), &mut Rng::with_seed(seed));
+
+            // A fill can leave no region big enough to keep, so there may be no floor at all
+            let (_, region_count) = cave.label_regions(|&v, _| v, Connectivity::Four);
+
+            assert!(region_count <= 1, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_cave_remove_small_regions() {
+        let generator = CaveGenerator {
+            min_region_size: 30,
+            connect_regions: false,
+            ..Default::default()
+        };
+
+        for seed in 0..8 {
+            let cave = generator.generate(UVec2::new(60, 40), &mut Rng::with_seed(seed));
+            let (labels, region_count) = cave.label_regions(|&v, _| v, Connectivity::Four);
+
+            for label in 1..=region_count {
+                assert!(
+                    labels.count_matches(|&l, _| l == label) >= 30,
+                    "seed {seed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_cave_smooth() {
+        let generator = CaveGenerator {
+            iterations: 1,
+            ..Default::default()
+        };
+
+        // A lone floor cell is surrounded by walls, so gets filled in
+        let mut cave = Matrix::splat(UVec2::splat(5), false);
+        cave.set(UVec2::splat(2), true);
+
+        generator.smooth(&mut cave);
+
+        assert_eq!(cave.count_matches(|&v, _| v), 0);
+    }
+}
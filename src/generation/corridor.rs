@@ -0,0 +1,172 @@
+use crate::*;
+
+/// An L-shaped corridor, from `start` to `corner` to `end`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Corridor {
+    pub start: UVec2,
+    pub corner: UVec2,
+    pub end: UVec2,
+}
+
+impl Corridor {
+    /// `horizontal_first` moves along x from `start` before turning, otherwise along y
+    pub fn l_shaped(start: UVec2, end: UVec2, horizontal_first: bool) -> Self {
+        let corner = if horizontal_first {
+            UVec2::new(end.x, start.y)
+        } else {
+            UVec2::new(start.x, end.y)
+        };
+
+        Self { start, corner, end }
+    }
+
+    /// Randomly turns horizontally or vertically first
+    #[inline]
+    pub fn random(start: UVec2, end: UVec2, rng: &mut Rng) -> Self {
+        Self::l_shaped(start, end, rng.gen_range(0..2) == 0)
+    }
+
+    /// Both straight sections
+    pub fn segments(&self) -> [ClosedRect<u32>; 2] {
+        [(self.start, self.corner), (self.corner, self.end)]
+            .map(|(from, to)| ClosedRect::from_corners(from.min(to), from.max(to)))
+    }
+
+    /// Every cell the corridor passes through, the corner only appears once
+    ///
+    /// Grouped by segment rather than in walking order, see `path`
+    pub fn positions(&self) -> Vec<UVec2> {
+        self.segments()
+            .into_iter()
+            .flat_map(|segment| segment.positions())
+            .unique()
+            .collect()
+    }
+
+    /// Every cell from `start` to `end` in the order they're walked, the corner only appears once
+    pub fn path(&self) -> Vec<UVec2> {
+        let mut path = Vec::with_capacity(self.length() as usize + 1);
+        path.push(self.start);
+
+        for (from, to) in [(self.start, self.corner), (self.corner, self.end)] {
+            let mut pos = from;
+
+            // Each segment is straight, so only one axis ever changes
+            while pos != to {
+                let axis = if pos.x != to.x { 0 } else { 1 };
+                pos[axis] = if to[axis] > pos[axis] {
+                    pos[axis] + 1
+                } else {
+                    pos[axis] - 1
+                };
+
+                path.push(pos);
+            }
+        }
+
+        path
+    }
+
+    #[inline]
+    pub fn length(&self) -> u32 {
+        self.start.distance_manhattan(self.corner) + self.corner.distance_manhattan(self.end)
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    /// Sets every cell of `corridor` to `value`, widened right and down to `width` cells
+    ///
+    /// Cells outside the matrix are skipped
+    pub fn carve_corridor(&mut self, corridor: &Corridor, width: u32, value: T) {
+        let size = self.size();
+        let extra = UVec2::splat(width.saturating_sub(1));
+
+        for segment in corridor.segments() {
+            let bottom_right = (segment.bottom_right + extra).min(size.saturating_sub(UVec2::ONE));
+
+            if segment.top_left.cmpgt(bottom_right).any() {
+                continue;
+            }
+
+            for pos in ClosedRect::<u32>::from_corners(segment.top_left, bottom_right).positions() {
+                self.set(pos, value.clone());
+            }
+        }
+    }
+}
+
+/// Carves an L-shaped corridor into `matrix` along each edge between `points`
+///
+/// Edges are index pairs, as returned by `Delaunay::edges` or `minimum_spanning_tree`
+pub fn connect_with_corridors<T: Clone>(
+    matrix: &mut Matrix<T>,
+    points: &[UVec2],
+    edges: &[(u32, u32)],
+    value: T,
+    rng: &mut Rng,
+) -> Vec<Corridor> {
+    edges
+        .iter()
+        .map(|&(a, b)| {
+            let corridor = Corridor::random(points[a as usize], points[b as usize], rng);
+
+            matrix.carve_corridor(&corridor, 1, value.clone());
+
+            corridor
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_carve_corridor() {
+        let corridor = Corridor::l_shaped(UVec2::new(1, 1), UVec2::new(4, 3), true);
+
+        assert_eq!(corridor.corner, UVec2::new(4, 1));
+        assert_eq!(corridor.length(), 5);
+        assert_eq!(corridor.positions().len(), 6);
+
+        let backwards = Corridor::l_shaped(UVec2::new(4, 3), UVec2::new(1, 1), false);
+        assert_eq!(
+            backwards.path(),
+            [
+                UVec2::new(4, 3),
+                UVec2::new(4, 2),
+                UVec2::new(4, 1),
+                UVec2::new(3, 1),
+                UVec2::new(2, 1),
+                UVec2::new(1, 1),
+            ]
+        );
+
+        let mut matrix = Matrix::splat(UVec2::splat(5), false);
+        matrix.carve_corridor(&corridor, 2, true);
+
+        // The second segment is widened off the right edge, so gets clipped
+        assert_eq!(matrix.count_matches(|&v, _| v), 10);
+        assert!(*matrix.get(UVec2::new(2, 2)));
+    }
+
+    #[test]
+    fn test_connect_with_corridors() {
+        let points = [UVec2::new(1, 1), UVec2::new(8, 2), UVec2::new(3, 7)];
+        let delaunay = Delaunay::new(points.iter().map(|p| p.as_vec2()).collect());
+
+        let mut matrix = Matrix::splat(UVec2::splat(10), false);
+        let corridors = connect_with_corridors(
+            &mut matrix,
+            &points,
+            &delaunay.minimum_spanning_tree(),
+            true,
+            &mut Rng::with_seed(5),
+        );
+
+        assert_eq!(corridors.len(), 2);
+
+        let (_, region_count) = matrix.label_regions(|&v, _| v, Connectivity::Four);
+        assert_eq!(region_count, 1);
+    }
+}
@@ -0,0 +1,17 @@
+mod bsp;
+mod cave;
+mod corridor;
+mod noise;
+mod rooms;
+mod sampling;
+mod walker;
+mod wfc;
+
+pub use bsp::*;
+pub use cave::*;
+pub use corridor::*;
+pub use noise::*;
+pub use rooms::*;
+pub use sampling::*;
+pub use walker::*;
+pub use wfc::*;
@@ -0,0 +1,615 @@
+use crate::*;
+
+use core::{array, f32::consts::TAU};
+
+/// A 2D coherent noise function
+pub trait NoiseFn {
+    /// Noise value at `point`, roughly in the range -1..1
+    fn get(&self, point: Vec2) -> f32;
+}
+
+impl<N: NoiseFn + ?Sized> NoiseFn for &N {
+    #[inline]
+    fn get(&self, point: Vec2) -> f32 {
+        (**self).get(point)
+    }
+}
+
+impl<N: NoiseFn + ?Sized> NoiseFn for Box<N> {
+    #[inline]
+    fn get(&self, point: Vec2) -> f32 {
+        (**self).get(point)
+    }
+}
+
+impl Matrix<f32> {
+    /// Samples `noise` at `(pos + offset) * scale` for every cell
+    ///
+    /// For seamless output, use noise with a period of `size * scale` (see `PermutationTable::with_period`)
+    pub fn from_noise(size: UVec2, noise: &impl NoiseFn, offset: Vec2, scale: f32) -> Self {
+        let elements = size
+            .positions()
+            .into_iter()
+            .map(|pos| noise.get((pos.as_vec2() + offset) * scale))
+            .collect();
+
+        Matrix::from_elements(elements, size)
+    }
+}
+
+/// Shuffled lattice hashes shared by the lattice noise types
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PermutationTable {
+    perm: [u8; 256],
+    period: Option<UVec2>,
+}
+
+impl PermutationTable {
+    pub fn new(rng: &mut Rng) -> Self {
+        let mut perm = [0_u8; 256];
+
+        for (i, v) in perm.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        shuffle(&mut perm, rng);
+
+        Self { perm, period: None }
+    }
+
+    /// A table that only depends on `seed`, for noise that must be identical across runs
+    pub fn from_seed(seed: u64) -> Self {
+        Self::new(&mut Rng::with_seed(seed))
+    }
+
+    /// Makes the noise repeat every `period` lattice cells, for seamless tiling
+    ///
+    /// `Simplex` can't repeat on its skewed lattice, so it samples 4D noise on a torus instead
+    pub fn with_period(mut self, period: UVec2) -> Self {
+        assert!(period.min_element() > 0, "Noise period must be non-zero");
+
+        self.period = Some(period);
+        self
+    }
+
+    #[inline]
+    pub fn period(&self) -> Option<UVec2> {
+        self.period
+    }
+
+    #[inline]
+    fn hash(&self, cell: IVec2) -> u8 {
+        let cell = match self.period {
+            Some(period) => cell.rem_euclid(period.as_ivec2()),
+            None => cell,
+        };
+
+        let x = self.perm[(cell.x & 255) as usize];
+
+        self.perm[(x as i32 + (cell.y & 255)) as usize & 255]
+    }
+
+    /// Hash with an extra salt, for values that need more than one random number per cell
+    #[inline]
+    fn hash_salted(&self, cell: IVec2, salt: u8) -> u8 {
+        self.perm[(self.hash(cell) ^ salt) as usize]
+    }
+
+    /// Hash of a 4D lattice cell, ignoring the period
+    #[inline]
+    fn hash_4d(&self, cell: [i32; 4]) -> u8 {
+        cell.into_iter().fold(0, |hash, v| {
+            self.perm[(hash as i32 + (v & 255)) as usize & 255]
+        })
+    }
+}
+
+/// Quintic smoothstep, with zero first and second derivatives at 0 and 1
+#[inline]
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Gradient noise on a square lattice
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Perlin {
+    pub table: PermutationTable,
+}
+
+impl Perlin {
+    pub fn new(rng: &mut Rng) -> Self {
+        Self {
+            table: PermutationTable::new(rng),
+        }
+    }
+
+    #[inline]
+    fn gradient_dot(&self, cell: IVec2, offset: Vec2) -> f32 {
+        match self.table.hash(cell) & 7 {
+            0 => offset.x + offset.y,
+            1 => -offset.x + offset.y,
+            2 => offset.x - offset.y,
+            3 => -offset.x - offset.y,
+            4 => offset.x,
+            5 => -offset.x,
+            6 => offset.y,
+            _ => -offset.y,
+        }
+    }
+}
+
+impl NoiseFn for Perlin {
+    fn get(&self, point: Vec2) -> f32 {
+        let floor = point.floor();
+        let cell = floor.as_ivec2();
+        let local = point - floor;
+
+        let n00 = self.gradient_dot(cell, local);
+        let n10 = self.gradient_dot(cell + IVec2::new(1, 0), local - Vec2::new(1.0, 0.0));
+        let n01 = self.gradient_dot(cell + IVec2::new(0, 1), local - Vec2::new(0.0, 1.0));
+        let n11 = self.gradient_dot(cell + IVec2::ONE, local - Vec2::ONE);
+
+        let u = fade(local.x);
+        let v = fade(local.y);
+
+        lerp(lerp(n00, n10, u), lerp(n01, n11, u), v)
+    }
+}
+
+/// Gradient noise on a triangular lattice
+///
+/// Fewer directional artifacts than `Perlin`. With a period it's sampled from 4D simplex noise
+/// wrapped around a torus, which gives the same feature size but a slightly different look
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Simplex {
+    pub table: PermutationTable,
+}
+
+impl Simplex {
+    pub fn new(rng: &mut Rng) -> Self {
+        Self {
+            table: PermutationTable::new(rng),
+        }
+    }
+
+    /// Maps each axis onto a circle with a circumference of its period, so both wrap
+    fn get_tiled(&self, point: Vec2, period: UVec2) -> f32 {
+        let period = period.as_vec2();
+        // Wrapping first keeps the angles exact far from the origin
+        let angle = point.rem_euclid(period) / period * TAU;
+        let radius = period / TAU;
+
+        self.get_4d([
+            radius.x * angle.x.cos(),
+            radius.x * angle.x.sin(),
+            radius.y * angle.y.cos(),
+            radius.y * angle.y.sin(),
+        ])
+    }
+
+    fn get_4d(&self, point: [f32; 4]) -> f32 {
+        const SKEW: f32 = 0.309_017; // (√5 - 1) / 4
+        const UNSKEW: f32 = 0.138_196_6; // (5 - √5) / 20
+
+        let skew = point.iter().sum::<f32>() * SKEW;
+        let skewed = point.map(|v| (v + skew).floor());
+        let unskew = skewed.iter().sum::<f32>() * UNSKEW;
+        let d0: [f32; 4] = array::from_fn(|i| point[i] - (skewed[i] - unskew));
+        let cell = skewed.map(|v| v as i32);
+
+        // The simplex steps along the axes from the largest offset to the smallest
+        let mut rank = [0; 4];
+        for i in 0..4 {
+            for j in i + 1..4 {
+                if d0[i] > d0[j] {
+                    rank[i] += 1;
+                } else {
+                    rank[j] += 1;
+                }
+            }
+        }
+
+        let mut sum = 0.0;
+
+        for corner in 0..5 {
+            let offset: [i32; 4] = array::from_fn(|i| (rank[i] + corner >= 4) as i32);
+            let d: [f32; 4] = array::from_fn(|i| d0[i] - offset[i] as f32 + corner as f32 * UNSKEW);
+            let t = 0.6 - d.iter().map(|v| v * v).sum::<f32>();
+
+            if t > 0.0 {
+                let hash = self.table.hash_4d(array::from_fn(|i| cell[i] + offset[i]));
+
+                sum += t * t * t * t * gradient_dot_4d(hash, d);
+            }
+        }
+
+        // Scales the result to roughly -1..1
+        sum * 27.0
+    }
+}
+
+/// Dot product with one of the 32 gradients that have a single zero and ±1 elsewhere
+#[inline]
+fn gradient_dot_4d(hash: u8, d: [f32; 4]) -> f32 {
+    let zero = (hash >> 3 & 3) as usize;
+    let mut sign_bit = 0;
+    let mut sum = 0.0;
+
+    for (i, v) in d.into_iter().enumerate() {
+        if i != zero {
+            sum += if hash >> sign_bit & 1 == 0 { v } else { -v };
+            sign_bit += 1;
+        }
+    }
+
+    sum
+}
+
+impl NoiseFn for Simplex {
+    fn get(&self, point: Vec2) -> f32 {
+        if let Some(period) = self.table.period() {
+            return self.get_tiled(point, period);
+        }
+
+        const SKEW: f32 = 0.366_025_42; // (√3 - 1) / 2
+        const UNSKEW: f32 = 0.211_324_87; // (3 - √3) / 6
+
+        const GRADIENTS: [Vec2; 8] = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(0.0, -1.0),
+        ];
+
+        let skewed = (point + Vec2::splat(point.element_sum() * SKEW)).floor();
+        let cell = skewed.as_ivec2();
+        let origin = skewed - Vec2::splat(skewed.element_sum() * UNSKEW);
+        let d0 = point - origin;
+
+        // Which triangle of the skewed cell the point is in
+        let middle = if d0.x > d0.y {
+            IVec2::new(1, 0)
+        } else {
+            IVec2::new(0, 1)
+        };
+
+        let d1 = d0 - middle.as_vec2() + Vec2::splat(UNSKEW);
+        let d2 = d0 - Vec2::ONE + Vec2::splat(2.0 * UNSKEW);
+
+        let corner = |offset: IVec2, d: Vec2| {
+            let t = 0.5 - d.length_squared();
+
+            if t <= 0.0 {
+                0.0
+            } else {
+                let gradient = GRADIENTS[(self.table.hash(cell + offset) & 7) as usize];
+
+                t * t * t * t * gradient.dot(d)
+            }
+        };
+
+        let sum = corner(IVec2::ZERO, d0) + corner(middle, d1) + corner(IVec2::ONE, d2);
+
+        // Scales the result to roughly -1..1
+        sum * 70.0
+    }
+}
+
+/// Smoothly interpolated random values at lattice points
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueNoise {
+    pub table: PermutationTable,
+}
+
+impl ValueNoise {
+    pub fn new(rng: &mut Rng) -> Self {
+        Self {
+            table: PermutationTable::new(rng),
+        }
+    }
+
+    #[inline]
+    fn value(&self, cell: IVec2) -> f32 {
+        self.table.hash(cell) as f32 / 127.5 - 1.0
+    }
+}
+
+impl NoiseFn for ValueNoise {
+    fn get(&self, point: Vec2) -> f32 {
+        let floor = point.floor();
+        let cell = floor.as_ivec2();
+        let local = point - floor;
+
+        let u = fade(local.x);
+        let v = fade(local.y);
+
+        lerp(
+            lerp(self.value(cell), self.value(cell + IVec2::new(1, 0)), u),
+            lerp(
+                self.value(cell + IVec2::new(0, 1)),
+                self.value(cell + IVec2::ONE),
+                u,
+            ),
+            v,
+        )
+    }
+}
+
+/// Which distances `Worley` noise returns
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WorleyReturn {
+    /// Distance to the closest feature point, gives round cells
+    F1,
+    /// Distance to the second closest feature point
+    F2,
+    /// Gives thin ridges along cell borders
+    F2MinusF1,
+}
+
+/// Cellular noise, based on the distance to randomly placed feature points (one per lattice cell)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Worley {
+    pub table: PermutationTable,
+    pub return_type: WorleyReturn,
+}
+
+impl Worley {
+    pub fn new(rng: &mut Rng) -> Self {
+        Self {
+            table: PermutationTable::new(rng),
+            return_type: WorleyReturn::F1,
+        }
+    }
+
+    #[inline]
+    fn feature_point(&self, cell: IVec2) -> Vec2 {
+        let x = self.table.hash_salted(cell, 0x5A) as f32 / 255.0;
+        let y = self.table.hash_salted(cell, 0xA5) as f32 / 255.0;
+
+        cell.as_vec2() + Vec2::new(x, y)
+    }
+}
+
+impl NoiseFn for Worley {
+    fn get(&self, point: Vec2) -> f32 {
+        let cell = point.floor().as_ivec2();
+
+        let mut f1 = f32::MAX;
+        let mut f2 = f32::MAX;
+
+        for offset in IVec2::splat(3).positions() {
+            let dist = point.distance(self.feature_point(cell + offset - IVec2::ONE));
+
+            if dist < f1 {
+                f2 = f1;
+                f1 = dist;
+            } else if dist < f2 {
+                f2 = dist;
+            }
+        }
+
+        let value = match self.return_type {
+            WorleyReturn::F1 => f1,
+            WorleyReturn::F2 => f2,
+            WorleyReturn::F2MinusF1 => f2 - f1,
+        };
+
+        // Distances are almost always within 0..1
+        (value * 2.0 - 1.0).min(1.0)
+    }
+}
+
+/// How octaves are combined in `Fractal` noise
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FractalMode {
+    /// Fractal Brownian motion, a plain sum of octaves
+    Fbm,
+    /// Sharp ridges where the noise crosses zero, good for mountain ranges
+    Ridged,
+    /// Rounded lumps, good for clouds and hills
+    Billow,
+}
+
+/// Sums several octaves of `noise` at increasing frequencies and decreasing amplitudes
+///
+/// Tiling is preserved as long as `lacunarity` is a whole number
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fractal<N: NoiseFn> {
+    pub noise: N,
+    pub mode: FractalMode,
+    pub octaves: u32,
+    /// Frequency multiplier between octaves
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves
+    pub gain: f32,
+}
+
+impl<N: NoiseFn> Fractal<N> {
+    pub fn new(noise: N, mode: FractalMode, octaves: u32) -> Self {
+        Self {
+            noise,
+            mode,
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl<N: NoiseFn> NoiseFn for Fractal<N> {
+    fn get(&self, point: Vec2) -> f32 {
+        let mut sum = 0.0;
+        let mut amplitude = 1.0;
+        let mut amplitude_sum = 0.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..self.octaves {
+            let n = self.noise.get(point * frequency);
+
+            let signal = match self.mode {
+                FractalMode::Fbm => n,
+                FractalMode::Ridged => {
+                    let ridge = 1.0 - n.abs();
+
+                    ridge * ridge * 2.0 - 1.0
+                }
+                FractalMode::Billow => n.abs() * 2.0 - 1.0,
+            };
+
+            sum += signal * amplitude;
+            amplitude_sum += amplitude;
+            amplitude *= self.gain;
+            frequency *= self.lacunarity;
+        }
+
+        if amplitude_sum == 0.0 {
+            0.0
+        } else {
+            sum / amplitude_sum
+        }
+    }
+}
+
+/// Offsets the sample point of `noise` by two samples of `warp`, for swirling, organic shapes
+#[derive(Clone, Debug, PartialEq)]
+pub struct DomainWarp<N: NoiseFn, W: NoiseFn> {
+    pub noise: N,
+    pub warp: W,
+    /// Maximum offset, in noise units
+    pub strength: f32,
+}
+
+impl<N: NoiseFn, W: NoiseFn> DomainWarp<N, W> {
+    pub fn new(noise: N, warp: W, strength: f32) -> Self {
+        Self {
+            noise,
+            warp,
+            strength,
+        }
+    }
+}
+
+impl<N: NoiseFn, W: NoiseFn> NoiseFn for DomainWarp<N, W> {
+    fn get(&self, point: Vec2) -> f32 {
+        // Arbitrary offset so the x and y warps aren't identical
+        const Y_OFFSET: Vec2 = Vec2::new(5.2, 1.3);
+
+        let warp = Vec2::new(self.warp.get(point), self.warp.get(point + Y_OFFSET));
+
+        self.noise.get(point + warp * self.strength)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_in_range(noise: &impl NoiseFn) {
+        for pos in UVec2::splat(40).positions() {
+            let v = noise.get(pos.as_vec2() * 0.173);
+
+            assert!((-1.0..=1.0).contains(&v), "{v} at {pos} is out of range");
+        }
+    }
+
+    #[test]
+    fn test_noise_range() {
+        for seed in 0..4 {
+            let mut rng = Rng::with_seed(seed);
+
+            assert_in_range(&Perlin::new(&mut rng));
+            assert_in_range(&Simplex::new(&mut rng));
+            assert_in_range(&Simplex {
+                table: PermutationTable::new(&mut rng).with_period(UVec2::new(5, 3)),
+            });
+            assert_in_range(&ValueNoise::new(&mut rng));
+            assert_in_range(&Worley::new(&mut rng));
+            assert_in_range(&Fractal::new(Perlin::new(&mut rng), FractalMode::Ridged, 4));
+        }
+    }
+
+    #[test]
+    fn test_noise_zero_at_lattice_points() {
+        let perlin = Perlin::new(&mut Rng::with_seed(3));
+
+        assert_eq!(perlin.get(Vec2::new(3.0, -2.0)), 0.0);
+        assert_eq!(perlin.get(Vec2::new(-7.0, 12.0)), 0.0);
+    }
+
+    #[test]
+    fn test_noise_same_seed() {
+        let size = UVec2::new(24, 16);
+        let sample = |noise: &dyn NoiseFn| Matrix::from_noise(size, &noise, Vec2::ZERO, 0.31);
+
+        let noises = |seed: u64| -> [Box<dyn NoiseFn>; 4] {
+            [
+                Box::new(Perlin {
+                    table: PermutationTable::from_seed(seed),
+                }),
+                Box::new(Simplex {
+                    table: PermutationTable::from_seed(seed),
+                }),
+                Box::new(ValueNoise {
+                    table: PermutationTable::from_seed(seed),
+                }),
+                Box::new(Worley {
+                    table: PermutationTable::from_seed(seed),
+                    return_type: WorleyReturn::F1,
+                }),
+            ]
+        };
+
+        for (a, b) in noises(42).iter().zip(noises(42).iter()) {
+            assert_eq!(sample(a.as_ref()), sample(b.as_ref()));
+        }
+        for (a, b) in noises(42).iter().zip(noises(43).iter()) {
+            assert_ne!(sample(a.as_ref()), sample(b.as_ref()));
+        }
+
+        assert_eq!(
+            PermutationTable::from_seed(7),
+            PermutationTable::from_seed(7)
+        );
+        assert_ne!(
+            PermutationTable::from_seed(7),
+            PermutationTable::from_seed(8)
+        );
+    }
+
+    #[test]
+    fn test_noise_tileable() {
+        let size = UVec2::new(32, 16);
+        let scale = 0.25;
+
+        let table =
+            PermutationTable::from_seed(11).with_period((size.as_vec2() * scale).as_uvec2());
+        let noises: [Box<dyn NoiseFn>; 2] = [
+            Box::new(Fractal::new(
+                Perlin {
+                    table: table.clone(),
+                },
+                FractalMode::Fbm,
+                3,
+            )),
+            Box::new(Fractal::new(Simplex { table }, FractalMode::Fbm, 3)),
+        ];
+
+        for noise in &noises {
+            let matrix = Matrix::from_noise(size, noise, Vec2::ZERO, scale);
+            let shifted = Matrix::from_noise(size, noise, size.as_vec2(), scale);
+
+            assert!(matrix.approx_eq(&shifted, 0.0001));
+            let first = *matrix.get(UVec2::ZERO);
+            assert!(matrix.iter().any(|&v| (v - first).abs() > 0.1));
+        }
+    }
+}
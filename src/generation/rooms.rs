@@ -0,0 +1,217 @@
+use crate::*;
+
+/// Places random non-overlapping rooms, then connects them with corridors
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoomGenerator {
+    /// Rooms placed before stopping, fewer are placed if they don't fit
+    pub room_count: u32,
+    /// Room placements tried before giving up
+    pub max_attempts: u32,
+    pub min_room_size: UVec2,
+    pub max_room_size: UVec2,
+    /// Minimum gap between rooms, and between rooms and the edge of the bounds
+    pub padding: u32,
+    /// Chance of keeping each extra (non spanning tree) connection, which adds loops
+    pub loop_chance: f32,
+    pub corridor_width: u32,
+}
+
+impl Default for RoomGenerator {
+    fn default() -> Self {
+        Self {
+            room_count: 12,
+            max_attempts: 500,
+            min_room_size: UVec2::splat(4),
+            max_room_size: UVec2::splat(10),
+            padding: 2,
+            loop_chance: 0.15,
+            corridor_width: 1,
+        }
+    }
+}
+
+/// Output of `RoomGenerator::generate`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomLayout {
+    /// Exclusive rects
+    pub rooms: Vec<Rect<u32>>,
+    pub corridors: Vec<Corridor>,
+    /// Indices of the rooms joined by each corridor
+    pub connections: Vec<(u32, u32)>,
+    /// Cells where corridors pass through the wall around a room
+    pub doors: Vec<UVec2>,
+    /// Rooms and corridors are `true`
+    pub matrix: Matrix<bool>,
+}
+
+impl RoomGenerator {
+    /// The matrix is sized to fit `bounds`, so positions match the Rects
+    pub fn generate(&self, bounds: Rect<u32>, rng: &mut Rng) -> RoomLayout {
+        let rooms = self.place_rooms(bounds, rng);
+
+        // Every room is connected by the spanning tree, the other triangulation edges add loops
+        let centers = rooms.iter().map(|room| room.center()).collect_vec();
+        let delaunay = Delaunay::new(centers.iter().map(|c| c.as_vec2()).collect());
+        let tree = delaunay.minimum_spanning_tree();
+
+        let mut connections = tree.clone();
+        connections.extend(
+            delaunay
+                .edges()
+                .into_iter()
+                .filter(|edge| !tree.contains(edge))
+                .filter(|_| rng.gen_range(0.0..1.0) < self.loop_chance),
+        );
+
+        let mut matrix = Matrix::splat(bounds.bottom_right, false);
+
+        for room in &rooms {
+            for pos in room.positions() {
+                matrix.set(pos, true);
+            }
+        }
+
+        // The ring of cells around each room, minus any cells of a neighbouring room it runs into
+        let walls = rooms
+            .iter()
+            .map(|room| {
+                room.expanded(1)
+                    .iter_border_positions()
+                    .filter(|&pos| !*matrix.get(pos))
+                    .collect::<HashSet<UVec2>>()
+            })
+            .collect_vec();
+
+        let mut corridors = Vec::with_capacity(connections.len());
+        let mut doors = Vec::new();
+
+        for &(a, b) in &connections {
+            let corridor = Corridor::random(centers[a as usize], centers[b as usize], rng);
+            let path = corridor.path();
+
+            // First wall cell on the way out of each room
+            let door_a = path.iter().find(|pos| walls[a as usize].contains(*pos));
+            let door_b = path
+                .iter()
+                .rev()
+                .find(|pos| walls[b as usize].contains(*pos));
+
+            for &door in door_a.into_iter().chain(door_b) {
+                if !doors.contains(&door) {
+                    doors.push(door);
+                }
+            }
+
+            matrix.carve_corridor(&corridor, self.corridor_width, true);
+            corridors.push(corridor);
+        }
+
+        RoomLayout {
+            rooms,
+            corridors,
+            connections,
+            doors,
+            matrix,
+        }
+    }
+
+    fn place_rooms(&self, bounds: Rect<u32>, rng: &mut Rng) -> Vec<Rect<u32>> {
+        let margin = UVec2::splat(self.padding.max(1));
+        let min_size = self.min_room_size.max(UVec2::ONE);
+        let max_size = self.max_room_size.max(min_size);
+
+        let mut rooms: Vec<Rect<u32>> = Vec::new();
+
+        for _ in 0..self.max_attempts {
+            if rooms.len() >= self.room_count as usize {
+                break;
+            }
+
+            let size = UVec2::new(
+                rng.gen_range(min_size.x..max_size.x + 1),
+                rng.gen_range(min_size.y..max_size.y + 1),
+            );
+
+            // Room top lefts that keep the margin from the edge of the bounds
+            let min = bounds.top_left + margin;
+            let max = bounds.bottom_right.saturating_sub(margin + size);
+
+            if min.cmpgt(max).any() {
+                continue;
+            }
+
+            let top_left = UVec2::new(
+                rng.gen_range(min.x..max.x + 1),
+                rng.gen_range(min.y..max.y + 1),
+            );
+            let room = Rect::from_top_left_and_size(top_left, size);
+
+            let padded = room.expanded(self.padding);
+
+            if rooms.iter().all(|other| !padded.overlaps(other)) {
+                rooms.push(room);
+            }
+        }
+
+        rooms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_doors_in_walls(layout: &RoomLayout) {
+        for door in &layout.doors {
+            assert!(*layout.matrix.get(*door));
+            assert!(
+                layout
+                    .rooms
+                    .iter()
+                    .any(|room| room.expanded(1).border_positions().contains(door))
+            );
+            assert!(layout.rooms.iter().all(|room| !room.contains_pos(*door)));
+        }
+    }
+
+    #[test]
+    fn test_room_generator() {
+        let generator = RoomGenerator::default();
+        let bounds = Rect::from_corners(UVec2::ZERO, UVec2::new(80, 60));
+
+        for seed in 0..8 {
+            let layout = generator.generate(bounds, &mut Rng::with_seed(seed));
+
+            assert!(layout.rooms.len() > 3, "seed {seed}");
+            assert!(layout.connections.len() >= layout.rooms.len() - 1);
+
+            for (i, a) in layout.rooms.iter().enumerate() {
+                for b in &layout.rooms[i + 1..] {
+                    assert!(!a.expanded(generator.padding).overlaps(b));
+                }
+            }
+
+            assert_doors_in_walls(&layout);
+
+            let (_, region_count) = layout.matrix.label_regions(|&v, _| v, Connectivity::Four);
+            assert_eq!(region_count, 1, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_room_generator_no_padding() {
+        // Rooms can touch, so one room's wall ring runs through its neighbour
+        let generator = RoomGenerator {
+            room_count: 30,
+            padding: 0,
+            ..Default::default()
+        };
+        let bounds = Rect::from_corners(UVec2::ZERO, UVec2::new(40, 30));
+
+        for seed in 0..8 {
+            let layout = generator.generate(bounds, &mut Rng::with_seed(seed));
+
+            assert_doors_in_walls(&layout);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::*;
+
+use core::f32::consts::{SQRT_2, TAU};
+
+/// Bridson's Poisson-disc sampling, points that are randomly placed but never closer than `radius`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PoissonDisc {
+    /// Minimum distance between points
+    pub radius: f32,
+    /// Candidates tried around each point before giving up on it, higher = denser but slower
+    pub attempts: u32,
+}
+
+impl PoissonDisc {
+    pub fn new(radius: f32) -> Self {
+        assert!(radius > 0.0, "Poisson-disc radius must be positive");
+
+        Self {
+            radius,
+            attempts: 30,
+        }
+    }
+
+    /// Samples points inside `bounds`
+    pub fn sample_rect(&self, bounds: Rect<f32>, rng: &mut Rng) -> Vec<Vec2> {
+        let seed = bounds.top_left + random_vec2(bounds.size(), rng);
+
+        self.sample(bounds, self.radius, |_| self.radius, |_| true, [seed], rng)
+    }
+
+    /// Samples cells of `mask` that are `true`
+    ///
+    /// Points are `radius` apart before being floored to their cells, so cells can be up to
+    /// `SQRT_2` closer than `radius`. Every disconnected area of the mask is sampled, no matter how small
+    pub fn sample_mask(&self, mask: &Matrix<bool>, rng: &mut Rng) -> Vec<UVec2> {
+        let bounds = Rect::from_top_left_and_size(Vec2::ZERO, mask.size().as_vec2());
+
+        let mut seeds = mask.extract_positions(|&v, _| v);
+        shuffle(&mut seeds, rng);
+
+        // Seeding from every cell center reaches areas the growth can't jump to
+        let points = self.sample(
+            bounds,
+            self.radius,
+            |_| self.radius,
+            |p| *mask.get(p.as_uvec2()),
+            seeds
+                .into_iter()
+                .map(|cell| cell.as_vec2() + Vec2::splat(0.5)),
+            rng,
+        );
+
+        let mut used = BitMatrix::new(mask.size());
+
+        points
+            .into_iter()
+            .map(|p| p.as_uvec2())
+            .filter(|&cell| used.insert(cell))
+            .collect()
+    }
+
+    /// Samples the area covered by `density`, one unit per cell
+    ///
+    /// The radius shrinks from `max_radius` at a density of 0, to `radius` at a density of 1 (values are clamped)
+    pub fn sample_density(
+        &self,
+        density: &Matrix<f32>,
+        max_radius: f32,
+        rng: &mut Rng,
+    ) -> Vec<Vec2> {
+        let size = density.size().as_vec2();
+        let bounds = Rect::from_top_left_and_size(Vec2::ZERO, size);
+        let max_radius = max_radius.max(self.radius);
+
+        let radius_at = |p: Vec2| {
+            let cell = p.as_uvec2().min(density.size() - UVec2::ONE);
+            let t = density.get(cell).clamp(0.0, 1.0);
+
+            max_radius + (self.radius - max_radius) * t
+        };
+
+        let seed = random_vec2(size, rng);
+
+        self.sample(bounds, max_radius, radius_at, |_| true, [seed], rng)
+    }
+
+    /// Core of Bridson's algorithm
+    ///
+    /// Grows outwards from each active point, trying candidates in an annulus of 1 to 2 radii.
+    /// Whenever the active list runs out, the next valid seed restarts it
+    fn sample(
+        &self,
+        bounds: Rect<f32>,
+        max_radius: f32,
+        radius_at: impl Fn(Vec2) -> f32,
+        // Only called for points inside `bounds`
+        is_valid: impl Fn(Vec2) -> bool,
+        seeds: impl IntoIterator<Item = Vec2>,
+        rng: &mut Rng,
+    ) -> Vec<Vec2> {
+        let size = bounds.size();
+
+        if size.min_element() <= 0.0 {
+            return Vec::new();
+        }
+
+        // No two points can share a grid cell, as the diagonal is the minimum radius
+        let cell_size = self.radius / SQRT_2;
+        let grid_size = (size / cell_size).ceil().as_uvec2();
+        let mut grid: Matrix<Option<u32>> = Matrix::splat(grid_size, None);
+        let search = (max_radius / cell_size).ceil() as i32;
+
+        let mut points: Vec<Vec2> = Vec::new();
+        let mut radii: Vec<f32> = Vec::new();
+        let mut active: Vec<u32> = Vec::new();
+
+        let grid_cell = |p: Vec2| {
+            ((p - bounds.top_left) / cell_size)
+                .as_uvec2()
+                .min(grid_size - UVec2::ONE)
+        };
+
+        let mut try_insert = |p: Vec2, points: &mut Vec<Vec2>, active: &mut Vec<u32>| {
+            let in_bounds = p.cmpge(bounds.top_left).all() && p.cmplt(bounds.bottom_right).all();
+
+            if !in_bounds || !is_valid(p) {
+                return false;
+            }
+
+            let radius = radius_at(p);
+            let cell = grid_cell(p).as_ivec2();
+
+            for offset in IVec2::splat(search * 2 + 1).positions() {
+                let other = cell + offset - IVec2::splat(search);
+
+                if other.min_element() < 0 || !other.as_uvec2().cmplt(grid_size).all() {
+                    continue;
+                }
+
+                if let Some(idx) = *grid.get(other.as_uvec2()) {
+                    let min_dist = radius.max(radii[idx as usize]);
+
+                    if p.distance_squared(points[idx as usize]) < min_dist * min_dist {
+                        return false;
+                    }
+                }
+            }
+
+            let idx = points.len() as u32;
+
+            grid.set(cell.as_uvec2(), Some(idx));
+            points.push(p);
+            radii.push(radius);
+            active.push(idx);
+
+            true
+        };
+
+        for seed in seeds {
+            if !try_insert(seed, &mut points, &mut active) {
+                continue;
+            }
+
+            while !active.is_empty() {
+                let active_idx = rng.gen_range(0..active.len());
+                let point = points[active[active_idx] as usize];
+                let radius = radius_at(point);
+
+                let found = (0..self.attempts).any(|_| {
+                    let angle = rng.gen_range(0.0..TAU);
+                    let dist = rng.gen_range(radius..radius * 2.0);
+                    let candidate = point + Vec2::from_angle(angle) * dist;
+
+                    try_insert(candidate, &mut points, &mut active)
+                });
+
+                if !found {
+                    active.swap_remove(active_idx);
+                }
+            }
+        }
+
+        points
+    }
+}
+
+/// One randomly offset point per grid cell of `spacing`
+///
+/// `jitter` is how far points can move from their cell center, 0 = regular grid, 1 = anywhere in the cell
+pub fn jittered_grid(bounds: Rect<f32>, spacing: Vec2, jitter: f32, rng: &mut Rng) -> Vec<Vec2> {
+    let counts = (bounds.size() / spacing).floor().as_uvec2();
+    let jitter = jitter.clamp(0.0, 1.0);
+
+    counts
+        .positions()
+        .into_iter()
+        .map(|cell| {
+            let center = bounds.top_left + (cell.as_vec2() + Vec2::splat(0.5)) * spacing;
+            let offset = (random_vec2(Vec2::ONE, rng) - Vec2::splat(0.5)) * jitter;
+
+            center + offset * spacing
+        })
+        .collect()
+}
+
+/// One random `true` cell from each `block_size` block of `mask`
+///
+/// Blocks without any `true` cells are skipped
+pub fn jittered_grid_cells(mask: &Matrix<bool>, block_size: UVec2, rng: &mut Rng) -> Vec<UVec2> {
+    let size = mask.size();
+    let block_counts = (size + block_size - UVec2::ONE) / block_size;
+
+    block_counts
+        .positions()
+        .into_iter()
+        .filter_map(|block| {
+            let top_left = block * block_size;
+            let bottom_right = (top_left + block_size).min(size);
+
+            let cells = Rect::<u32>::from_corners(top_left, bottom_right)
+                .positions()
+                .into_iter()
+                .filter(|&pos| *mask.get(pos))
+                .collect_vec();
+
+            if cells.is_empty() {
+                None
+            } else {
+                Some(cells[rng.gen_range(0..cells.len())])
+            }
+        })
+        .collect()
+}
+
+/// Random point in `0..size`
+#[inline]
+fn random_vec2(size: Vec2, rng: &mut Rng) -> Vec2 {
+    let mut coord = |max: f32| {
+        if max > 0.0 {
+            rng.gen_range(0.0..max)
+        } else {
+            0.0
+        }
+    };
+
+    Vec2::new(coord(size.x), coord(size.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn min_distance(points: &[Vec2]) -> f32 {
+        points
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| a.distance(*b))
+            .fold(f32::MAX, f32::min)
+    }
+
+    #[test]
+    fn test_poisson_disc_rect() {
+        let bounds = Rect::from_corners(Vec2::new(10.0, 5.0), Vec2::new(50.0, 35.0));
+
+        for seed in 0..8 {
+            let points = PoissonDisc::new(3.0).sample_rect(bounds, &mut Rng::with_seed(seed));
+
+            // A 40x30 area fits roughly 100 points at this radius
+            assert!(points.len() > 60, "seed {seed}");
+            assert!(min_distance(&points) >= 3.0, "seed {seed}");
+
+            for p in points {
+                assert!(p.cmpge(bounds.top_left).all() && p.cmplt(bounds.bottom_right).all());
+            }
+        }
+    }
+
+    #[test]
+    fn test_poisson_disc_mask() {
+        let mut mask = Matrix::splat(UVec2::new(30, 30), false);
+
+        for pos in Rect::<u32>::from_corners(UVec2::ZERO, UVec2::new(10, 30)).positions() {
+            mask.set(pos, true);
+        }
+        // An island that can't be reached from the other area
+        mask.set(UVec2::new(25, 25), true);
+
+        for seed in 0..8 {
+            let cells = PoissonDisc::new(2.5).sample_mask(&mask, &mut Rng::with_seed(seed));
+
+            assert!(cells.iter().all(|&cell| *mask.get(cell)));
+            assert!(cells.contains(&UVec2::new(25, 25)), "seed {seed}");
+
+            let cell_points = cells.iter().map(|cell| cell.as_vec2()).collect_vec();
+            assert!(min_distance(&cell_points) >= 2.5 - SQRT_2, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_poisson_disc_density() {
+        // Dense on the left, sparse on the right
+        let density = Matrix::from_elements(
+            UVec2::new(40, 20)
+                .positions()
+                .into_iter()
+                .map(|pos| if pos.x < 20 { 1.0 } else { 0.0 })
+                .collect(),
+            UVec2::new(40, 20),
+        );
+
+        for seed in 0..8 {
+            let points =
+                PoissonDisc::new(1.5).sample_density(&density, 5.0, &mut Rng::with_seed(seed));
+            let left = points.iter().filter(|p| p.x < 20.0).count();
+
+            assert!(left > (points.len() - left) * 3, "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use crate::*;
+
+/// Cells carved around a walker each step
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Brush {
+    Single,
+    /// `radius` cells out in each direction, (2 * radius + 1)² cells
+    Square(u32),
+    /// Cells within `radius` of the walker
+    Circle(f32),
+}
+
+impl Brush {
+    pub fn offsets(&self) -> Vec<IVec2> {
+        match *self {
+            Brush::Single => vec![IVec2::ZERO],
+            Brush::Square(radius) => {
+                let r = radius as i32;
+
+                IVec2::splat(r * 2 + 1)
+                    .positions()
+                    .into_iter()
+                    .map(|p| p - IVec2::splat(r))
+                    .collect()
+            }
+            Brush::Circle(radius) => {
+                let r = radius.max(0.0) as i32;
+
+                IVec2::splat(r * 2 + 1)
+                    .positions()
+                    .into_iter()
+                    .map(|p| p - IVec2::splat(r))
+                    .filter(|p| p.as_vec2().length() <= radius)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Drunkard's walk carver, agents wander randomly and carve out everything they pass over
+///
+/// Cells are `true` once carved
+#[derive(Clone, Debug, PartialEq)]
+pub struct RandomWalker {
+    /// Chance each step to turn left, 90° (or 45° with `diagonal`)
+    pub turn_left_chance: f32,
+    /// Chance each step to turn right, 90° (or 45° with `diagonal`)
+    pub turn_right_chance: f32,
+    /// Chance each step to turn around
+    pub reverse_chance: f32,
+    /// Allows walking diagonally, otherwise walkers only face cardinal directions
+    ///
+    /// Diagonal steps also carve the cell beside the corner they cut, so tunnels stay 4-connected
+    pub diagonal: bool,
+    pub brush: Brush,
+    /// Chance each step for a walker to split off a new walker
+    pub spawn_chance: f32,
+    /// Chance each step for a walker to stop, the last walker never stops
+    pub death_chance: f32,
+    pub max_walkers: u32,
+    /// Stops once this fraction of all cells are carved
+    pub target_fill: f32,
+    /// Stops after this many steps, even if `target_fill` isn't reached
+    pub max_steps: u32,
+}
+
+impl Default for RandomWalker {
+    fn default() -> Self {
+        Self {
+            turn_left_chance: 0.15,
+            turn_right_chance: 0.15,
+            reverse_chance: 0.02,
+            diagonal: false,
+            brush: Brush::Single,
+            spawn_chance: 0.05,
+            death_chance: 0.05,
+            max_walkers: 8,
+            target_fill: 0.4,
+            max_steps: 100_000,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Walker {
+    pos: UVec2,
+    dir: Dir,
+}
+
+impl RandomWalker {
+    /// Carves a new matrix, starting from the center
+    pub fn generate(&self, size: UVec2, rng: &mut Rng) -> Matrix<bool> {
+        let mut matrix = Matrix::splat(size, false);
+
+        self.carve(&mut matrix, size / 2, rng);
+
+        matrix
+    }
+
+    /// Carves into an existing matrix from `start`
+    ///
+    /// Walkers stay off the outermost cells, so the result is always enclosed.
+    /// Returns the number of cells carved
+    pub fn carve(&self, matrix: &mut Matrix<bool>, start: UVec2, rng: &mut Rng) -> u32 {
+        let size = matrix.size();
+        let offsets = self.brush.offsets();
+
+        // Keeps the brush off the outermost cells
+        let margin = offsets
+            .iter()
+            .map(|offset| offset.abs().max_element() as u32)
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        if size.min_element() <= margin * 2 {
+            return 0;
+        }
+
+        let min = UVec2::splat(margin);
+        let max = size - UVec2::splat(margin + 1);
+
+        let target = (size.element_product() as f32 * self.target_fill.clamp(0.0, 1.0)) as u32;
+        let turn_step = if self.diagonal { 1 } else { 2 };
+
+        let mut filled = matrix.count_matches(|&v, _| v);
+        let mut carved = 0;
+        let mut walkers = vec![Walker {
+            pos: start.clamp(min, max),
+            dir: self.random_dir(rng),
+        }];
+
+        for _ in 0..self.max_steps {
+            if filled >= target {
+                break;
+            }
+
+            let mut idx = 0;
+
+            while idx < walkers.len() {
+                let walker = &mut walkers[idx];
+
+                let count = stamp(matrix, walker.pos, &offsets);
+                filled += count;
+                carved += count;
+
+                let roll = rng.gen_range(0.0..1.0);
+
+                if roll < self.turn_left_chance {
+                    walker.dir = walker.dir.turn(-turn_step);
+                } else if roll < self.turn_left_chance + self.turn_right_chance {
+                    walker.dir = walker.dir.turn(turn_step);
+                } else if roll
+                    < self.turn_left_chance + self.turn_right_chance + self.reverse_chance
+                {
+                    walker.dir = walker.dir.opposite();
+                }
+
+                let next = walker.pos.as_ivec2() + walker.dir.to_vector();
+
+                if next.cmplt(min.as_ivec2()).any() || next.cmpgt(max.as_ivec2()).any() {
+                    // Bounce off the edge
+                    walker.dir = walker.dir.opposite();
+                } else {
+                    // Cutting a corner would leave cells only touching diagonally
+                    if next.x != walker.pos.x as i32 && next.y != walker.pos.y as i32 {
+                        let count =
+                            stamp(matrix, UVec2::new(next.x as u32, walker.pos.y), &offsets);
+                        filled += count;
+                        carved += count;
+                    }
+
+                    walker.pos = next.as_uvec2();
+                }
+
+                let walker = *walker;
+
+                if walkers.len() < self.max_walkers as usize
+                    && rng.gen_range(0.0..1.0) < self.spawn_chance
+                {
+                    walkers.push(Walker {
+                        pos: walker.pos,
+                        dir: self.random_dir(rng),
+                    });
+                }
+
+                if walkers.len() > 1 && rng.gen_range(0.0..1.0) < self.death_chance {
+                    walkers.swap_remove(idx);
+                } else {
+                    idx += 1;
+                }
+            }
+        }
+
+        carved
+    }
+
+    fn random_dir(&self, rng: &mut Rng) -> Dir {
+        if self.diagonal {
+            Dir::ALL[rng.gen_range(0..8)]
+        } else {
+            Dir::ALL_CARDINAL[rng.gen_range(0..4)]
+        }
+    }
+}
+
+/// Carves `offsets` around `pos`, returning how many cells weren't already carved
+fn stamp(matrix: &mut Matrix<bool>, pos: UVec2, offsets: &[IVec2]) -> u32 {
+    let mut count = 0;
+
+    for offset in offsets {
+        let pos = (pos.as_ivec2() + *offset).as_uvec2();
+
+        if !*matrix.get(pos) {
+            matrix.set(pos, true);
+            count += 1;
+        }
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_walker() {
+        let walker = RandomWalker::default();
+        let size = UVec2::new(40, 30);
+
+        for seed in 0..8 {
+            let matrix = walker.generate(size, &mut Rng::with_seed(seed));
+            let filled = matrix.count_matches(|&v, _| v);
+
+            assert!(filled as f32 >= size.element_product() as f32 * walker.target_fill);
+
+            // Walkers only spawn where another walker already is, so everything is connected
+            let (_, region_count) = matrix.label_regions(|&v, _| v, Connectivity::Four);
+            assert_eq!(region_count, 1, "seed {seed}");
+
+            // The border is never carved
+            for pos in Rect::<u32>::from_corners(UVec2::ZERO, size).border_positions() {
+                assert!(!*matrix.get(pos), "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_walker_diagonal() {
+        let walker = RandomWalker {
+            diagonal: true,
+            turn_left_chance: 0.3,
+            turn_right_chance: 0.3,
+            ..Default::default()
+        };
+        let size = UVec2::new(30, 40);
+
+        for seed in 0..8 {
+            let matrix = walker.generate(size, &mut Rng::with_seed(seed));
+
+            // Corner cuts are filled in, so cardinal movement reaches every carved cell
+            let (_, region_count) = matrix.label_regions(|&v, _| v, Connectivity::Four);
+            assert_eq!(region_count, 1, "seed {seed}");
+
+            for pos in Rect::<u32>::from_corners(UVec2::ZERO, size).border_positions() {
+                assert!(!*matrix.get(pos), "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_walker_same_seed() {
+        let size = UVec2::new(32, 24);
+
+        for walker in [
+            RandomWalker::default(),
+            RandomWalker {
+                diagonal: true,
+                brush: Brush::Circle(1.5),
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(
+                walker.generate(size, &mut Rng::with_seed(7)),
+                walker.generate(size, &mut Rng::with_seed(7))
+            );
+        }
+    }
+
+    #[test]
+    fn test_brush_offsets() {
+        assert_eq!(Brush::Single.offsets(), vec![IVec2::ZERO]);
+        assert_eq!(Brush::Square(1).offsets().len(), 9);
+        assert_eq!(Brush::Circle(1.0).offsets().len(), 5);
+    }
+}
@@ -0,0 +1,694 @@
+use crate::*;
+
+use core::{cmp::Ordering, fmt};
+use std::collections::BinaryHeap;
+
+/// Extra patterns added when learning from a sample
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PatternSymmetry {
+    None,
+    /// All 4 rotations
+    Rotate,
+    /// All 4 rotations, and their mirror images
+    RotateAndReflect,
+}
+
+/// The patterns (or tiles) a `WaveFunctionCollapse` can place, and which can sit next to each other
+#[derive(Clone, Debug, PartialEq)]
+pub struct WfcRules<T> {
+    /// Output value of each pattern
+    values: Vec<T>,
+    weights: Vec<f32>,
+    /// `adjacency[dir][a]` = patterns allowed in `Dir::ALL_CARDINAL[dir]` of `a`
+    adjacency: [Vec<Vec<u32>>; 4],
+}
+
+impl<T: Clone + PartialEq + Debug> WfcRules<T> {
+    /// Empty rules for the tiled model, see `add_tile` and `allow`
+    pub fn new() -> Self {
+        Self {
+            values: Vec::new(),
+            weights: Vec::new(),
+            adjacency: Default::default(),
+        }
+    }
+
+    /// Overlapping model, learns every `n` x `n` pattern in `sample`
+    ///
+    /// Patterns are weighted by how often they appear, and can sit next to each other if they agree where they overlap.
+    /// `wrap_sample` also reads patterns that cross the edges of the sample
+    pub fn from_sample(
+        sample: &Matrix<T>,
+        n: u32,
+        symmetry: PatternSymmetry,
+        wrap_sample: bool,
+    ) -> Self {
+        assert!(n > 0, "Pattern size must be non-zero");
+
+        let size = sample.size();
+        let origins = if wrap_sample {
+            size
+        } else {
+            (size + UVec2::ONE).saturating_sub(UVec2::splat(n))
+        };
+
+        let mut patterns: Vec<Matrix<T>> = Vec::new();
+        let mut weights: Vec<f32> = Vec::new();
+
+        for origin in origins.positions() {
+            let elements = UVec2::splat(n)
+                .positions()
+                .into_iter()
+                .map(|offset| sample.get((origin + offset) % size).clone())
+                .collect();
+            let pattern = Matrix::from_elements(elements, UVec2::splat(n));
+
+            for variant in symmetry_variants(pattern, symmetry) {
+                match patterns.iter().position(|p| *p == variant) {
+                    Some(idx) => weights[idx] += 1.0,
+                    None => {
+                        patterns.push(variant);
+                        weights.push(1.0);
+                    }
+                }
+            }
+        }
+
+        let mut rules = Self {
+            values: patterns
+                .iter()
+                .map(|p| p.get(UVec2::ZERO).clone())
+                .collect(),
+            weights,
+            adjacency: Default::default(),
+        };
+
+        for (dir_idx, dir) in Dir::ALL_CARDINAL.into_iter().enumerate() {
+            rules.adjacency[dir_idx] = patterns
+                .iter()
+                .map(|a| {
+                    (0..patterns.len() as u32)
+                        .filter(|&b| patterns_agree(a, &patterns[b as usize], dir.to_vector()))
+                        .collect()
+                })
+                .collect();
+        }
+
+        rules
+    }
+
+    /// Adds a tile for the tiled model, returning its index
+    ///
+    /// Tiles with a higher weight are picked more often.
+    /// Panics if `weight` isn't positive and finite
+    pub fn add_tile(&mut self, value: T, weight: f32) -> u32 {
+        assert!(
+            weight > 0.0 && weight.is_finite(),
+            "Tile weight must be positive and finite, got {weight}"
+        );
+
+        self.values.push(value);
+        self.weights.push(weight);
+
+        for adjacency in &mut self.adjacency {
+            adjacency.push(Vec::new());
+        }
+
+        self.values.len() as u32 - 1
+    }
+
+    /// Allows tile `b` to be placed in `dir` of tile `a` (and `a` in the opposite direction of `b`)
+    ///
+    /// `dir` must be cardinal
+    pub fn allow(&mut self, a: u32, dir: Dir, b: u32) {
+        for (from, dir, to) in [(a, dir, b), (b, dir.opposite(), a)] {
+            let allowed = &mut self.adjacency[cardinal_idx(dir)][from as usize];
+
+            if !allowed.contains(&to) {
+                allowed.push(to);
+            }
+        }
+    }
+
+    /// Number of patterns or tiles
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<T: Clone + PartialEq + Debug> Default for WfcRules<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fills a `Matrix` so every pair of neighbouring cells follows the `WfcRules`
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaveFunctionCollapse<T> {
+    pub rules: WfcRules<T>,
+    /// Treats the left & right and top & bottom edges of the output as neighbours
+    pub wrapping: bool,
+    /// Contradictions undone before giving up
+    pub max_backtracks: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WfcError {
+    /// No patterns can be placed at `pos`, even after backtracking through every choice
+    Contradiction(UVec2),
+    /// `max_backtracks` was reached
+    BacktrackLimit,
+    /// A fixed cell is outside the output
+    FixedCellOutOfBounds(UVec2),
+    NoPatterns,
+}
+
+impl fmt::Display for WfcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contradiction(pos) => write!(f, "no pattern fits at {pos}"),
+            Self::BacktrackLimit => write!(f, "backtrack limit reached"),
+            Self::FixedCellOutOfBounds(pos) => write!(f, "fixed cell {pos} is out of bounds"),
+            Self::NoPatterns => write!(f, "rules contain no patterns"),
+        }
+    }
+}
+
+impl std::error::Error for WfcError {}
+
+impl<T: Clone + PartialEq + Debug> WaveFunctionCollapse<T> {
+    pub fn new(rules: WfcRules<T>) -> Self {
+        Self {
+            rules,
+            wrapping: false,
+            max_backtracks: 1000,
+        }
+    }
+
+    /// Collapses a `size` output, with the `fixed` cells set beforehand
+    ///
+    /// Repeatedly picks the undecided cell with the lowest entropy, chooses a weighted random pattern for it,
+    /// and removes the patterns that can no longer fit from the cells around it.
+    /// On a contradiction the last choice is undone and banned
+    pub fn generate(
+        &self,
+        size: UVec2,
+        fixed: &[(UVec2, T)],
+        rng: &mut Rng,
+    ) -> Result<Matrix<T>, WfcError> {
+        if self.rules.is_empty() {
+            return Err(WfcError::NoPatterns);
+        }
+
+        let mut solver = Solver::new(&self.rules, size, self.wrapping, rng);
+
+        for (pos, value) in fixed {
+            if !Matrix::<T>::is_in_bounds_multi(*pos, size) {
+                return Err(WfcError::FixedCellOutOfBounds(*pos));
+            }
+
+            let cell = solver.idx(*pos);
+
+            for (pattern, pattern_value) in self.rules.values.iter().enumerate() {
+                if pattern_value != value {
+                    solver.ban(cell, pattern);
+                }
+            }
+        }
+
+        solver.propagate()?;
+
+        // Length of the ban log before each choice, along with the choice
+        let mut history: Vec<(usize, usize, usize)> = Vec::new();
+        let mut backtracks = 0;
+
+        while let Some(cell) = solver.lowest_entropy_cell() {
+            let pattern = solver.choose_pattern(cell, rng);
+            history.push((solver.bans.len(), cell, pattern));
+
+            solver.collapse(cell, pattern);
+            let mut result = solver.propagate();
+
+            while let Err(err) = result {
+                let Some((ban_count, cell, pattern)) = history.pop() else {
+                    return Err(err);
+                };
+
+                backtracks += 1;
+                if backtracks > self.max_backtracks {
+                    return Err(WfcError::BacktrackLimit);
+                }
+
+                solver.undo_bans(ban_count);
+                solver.ban(cell, pattern);
+                result = solver.propagate();
+            }
+        }
+
+        Ok(solver.output())
+    }
+}
+
+/// Possible patterns of every cell, plus cached totals for entropy
+#[derive(Debug)]
+struct WaveState {
+    /// `words_per_cell` bits per cell
+    possible: Vec<u64>,
+    counts: Vec<u32>,
+    weight_sums: Vec<f32>,
+    /// Sum of `weight * ln(weight)`
+    weight_log_sums: Vec<f32>,
+}
+
+struct Solver<'a, T> {
+    rules: &'a WfcRules<T>,
+    size: UVec2,
+    wrapping: bool,
+    words_per_cell: usize,
+    /// `adjacency` as bit sets
+    support: [Vec<Vec<u64>>; 4],
+    state: WaveState,
+    /// Cells whose possible patterns changed since the last propagation
+    dirty: Vec<usize>,
+    /// Every `(cell, pattern)` banned so far, undone in reverse when backtracking
+    bans: Vec<(usize, usize)>,
+    /// Undecided cells by entropy. Entries go stale as cells change, and are skipped when popped
+    entropy_heap: BinaryHeap<EntropyEntry>,
+    /// Added to each cell's entropy to break ties randomly
+    noise: Vec<f32>,
+}
+
+/// Orders by lowest entropy first in a `BinaryHeap`
+#[derive(Copy, Clone, Debug)]
+struct EntropyEntry {
+    entropy: f32,
+    cell: usize,
+}
+
+impl PartialEq for EntropyEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for EntropyEntry {}
+
+impl PartialOrd for EntropyEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for EntropyEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .entropy
+            .total_cmp(&self.entropy)
+            .then(other.cell.cmp(&self.cell))
+    }
+}
+
+impl<'a, T: Clone + PartialEq + Debug> Solver<'a, T> {
+    fn new(rules: &'a WfcRules<T>, size: UVec2, wrapping: bool, rng: &mut Rng) -> Self {
+        let pattern_count = rules.len();
+        let cell_count = size.element_product() as usize;
+        let words_per_cell = pattern_count.div_ceil(64);
+
+        let support = rules.adjacency.clone().map(|adjacency| {
+            adjacency
+                .into_iter()
+                .map(|allowed| {
+                    let mut bits = vec![0_u64; words_per_cell];
+
+                    for b in allowed {
+                        bits[b as usize / 64] |= 1 << (b % 64);
+                    }
+
+                    bits
+                })
+                .collect()
+        });
+
+        let mut all_patterns = vec![0_u64; words_per_cell];
+        for pattern in 0..pattern_count {
+            all_patterns[pattern / 64] |= 1 << (pattern % 64);
+        }
+
+        let weight_sum = rules.weights.iter().sum();
+        let weight_log_sum = rules.weights.iter().map(|&w| w * w.ln()).sum();
+
+        let mut solver = Self {
+            rules,
+            size,
+            wrapping,
+            words_per_cell,
+            support,
+            state: WaveState {
+                possible: all_patterns.repeat(cell_count),
+                counts: vec![pattern_count as u32; cell_count],
+                weight_sums: vec![weight_sum; cell_count],
+                weight_log_sums: vec![weight_log_sum; cell_count],
+            },
+            dirty: Vec::new(),
+            bans: Vec::new(),
+            entropy_heap: BinaryHeap::with_capacity(cell_count),
+            noise: (0..cell_count)
+                .map(|_| rng.gen_range(0.0..0.000_001))
+                .collect(),
+        };
+
+        for cell in 0..cell_count {
+            solver.push_entropy(cell);
+        }
+
+        solver
+    }
+
+    #[inline]
+    fn idx(&self, pos: UVec2) -> usize {
+        (pos.y * self.size.x + pos.x) as usize
+    }
+
+    #[inline]
+    fn pos(&self, idx: usize) -> UVec2 {
+        UVec2::new(idx as u32 % self.size.x, idx as u32 / self.size.x)
+    }
+
+    #[inline]
+    fn words(&self, cell: usize) -> &[u64] {
+        &self.state.possible[cell * self.words_per_cell..(cell + 1) * self.words_per_cell]
+    }
+
+    #[inline]
+    fn is_possible(&self, cell: usize, pattern: usize) -> bool {
+        self.words(cell)[pattern / 64] & (1 << (pattern % 64)) != 0
+    }
+
+    fn patterns(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.rules.len()).filter(move |&p| self.is_possible(cell, p))
+    }
+
+    fn ban(&mut self, cell: usize, pattern: usize) {
+        if !self.is_possible(cell, pattern) {
+            return;
+        }
+
+        self.state.possible[cell * self.words_per_cell + pattern / 64] &= !(1 << (pattern % 64));
+
+        let weight = self.rules.weights[pattern];
+        self.state.counts[cell] -= 1;
+        self.state.weight_sums[cell] -= weight;
+        self.state.weight_log_sums[cell] -= weight * weight.ln();
+
+        self.bans.push((cell, pattern));
+        self.dirty.push(cell);
+        self.push_entropy(cell);
+    }
+
+    /// Restores every pattern banned after the first `ban_count` bans
+    fn undo_bans(&mut self, ban_count: usize) {
+        self.dirty.clear();
+
+        while self.bans.len() > ban_count {
+            let (cell, pattern) = self.bans.pop().unwrap();
+
+            self.state.possible[cell * self.words_per_cell + pattern / 64] |= 1 << (pattern % 64);
+
+            let weight = self.rules.weights[pattern];
+            self.state.counts[cell] += 1;
+            self.state.weight_sums[cell] += weight;
+            self.state.weight_log_sums[cell] += weight * weight.ln();
+
+            self.push_entropy(cell);
+        }
+    }
+
+    #[inline]
+    fn entropy(&self, cell: usize) -> f32 {
+        let sum = self.state.weight_sums[cell];
+
+        sum.ln() - self.state.weight_log_sums[cell] / sum + self.noise[cell]
+    }
+
+    /// Queues `cell` for `lowest_entropy_cell` if it's still undecided
+    fn push_entropy(&mut self, cell: usize) {
+        if self.state.counts[cell] > 1 {
+            self.entropy_heap.push(EntropyEntry {
+                entropy: self.entropy(cell),
+                cell,
+            });
+        }
+    }
+
+    fn collapse(&mut self, cell: usize, chosen: usize) {
+        for pattern in 0..self.rules.len() {
+            if pattern != chosen {
+                self.ban(cell, pattern);
+            }
+        }
+    }
+
+    fn neighbour(&self, cell: usize, dir: Dir) -> Option<usize> {
+        let pos = self.pos(cell).as_ivec2() + dir.to_vector();
+        let size = self.size.as_ivec2();
+
+        let pos = if self.wrapping {
+            pos.rem_euclid(size)
+        } else if pos.cmpge(IVec2::ZERO).all() && pos.cmplt(size).all() {
+            pos
+        } else {
+            return None;
+        };
+
+        Some(self.idx(pos.as_uvec2()))
+    }
+
+    /// Removes patterns unsupported by their neighbours until nothing changes
+    fn propagate(&mut self) -> Result<(), WfcError> {
+        let mut supported = vec![0_u64; self.words_per_cell];
+
+        while let Some(cell) = self.dirty.pop() {
+            if self.state.counts[cell] == 0 {
+                self.dirty.clear();
+
+                return Err(WfcError::Contradiction(self.pos(cell)));
+            }
+
+            for (dir_idx, dir) in Dir::ALL_CARDINAL.into_iter().enumerate() {
+                let Some(neighbour) = self.neighbour(cell, dir) else {
+                    continue;
+                };
+
+                supported.fill(0);
+
+                for pattern in self.patterns(cell) {
+                    for (s, &bits) in supported.iter_mut().zip(&self.support[dir_idx][pattern]) {
+                        *s |= bits;
+                    }
+                }
+
+                let banned = (0..self.rules.len())
+                    .filter(|&p| {
+                        self.is_possible(neighbour, p) && supported[p / 64] & (1 << (p % 64)) == 0
+                    })
+                    .collect_vec();
+
+                for pattern in banned {
+                    self.ban(neighbour, pattern);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Undecided cell with the lowest entropy, None once every cell is decided
+    fn lowest_entropy_cell(&mut self) -> Option<usize> {
+        while let Some(EntropyEntry { entropy, cell }) = self.entropy_heap.pop() {
+            // Skip entries from before the cell last changed
+            if self.state.counts[cell] > 1 && entropy == self.entropy(cell) {
+                return Some(cell);
+            }
+        }
+
+        None
+    }
+
+    fn choose_pattern(&self, cell: usize, rng: &mut Rng) -> usize {
+        let total = self.state.weight_sums[cell];
+        let mut target = rng.gen_range(0.0..total.max(f32::MIN_POSITIVE));
+        let mut last = 0;
+
+        for pattern in self.patterns(cell) {
+            let weight = self.rules.weights[pattern];
+
+            if target < weight {
+                return pattern;
+            }
+
+            target -= weight;
+            last = pattern;
+        }
+
+        // Only reached through float rounding
+        last
+    }
+
+    fn output(&self) -> Matrix<T> {
+        let elements = (0..self.state.counts.len())
+            .map(|cell| {
+                let pattern = self.patterns(cell).next().unwrap();
+
+                self.rules.values[pattern].clone()
+            })
+            .collect();
+
+        Matrix::from_elements(elements, self.size)
+    }
+}
+
+#[inline]
+fn cardinal_idx(dir: Dir) -> usize {
+    match dir {
+        Dir::N => 0,
+        Dir::E => 1,
+        Dir::S => 2,
+        Dir::W => 3,
+        _ => panic!("WFC adjacency directions must be cardinal, got {dir}"),
+    }
+}
+
+fn symmetry_variants<T: Clone + Debug>(
+    pattern: Matrix<T>,
+    symmetry: PatternSymmetry,
+) -> Vec<Matrix<T>> {
+    let rotations = |p: &Matrix<T>| {
+        [
+            p.clone(),
+            p.rotate(MatrixRotation::D90),
+            p.rotate(MatrixRotation::D180),
+            p.rotate(MatrixRotation::D270),
+        ]
+    };
+
+    match symmetry {
+        PatternSymmetry::None => vec![pattern],
+        PatternSymmetry::Rotate => rotations(&pattern).to_vec(),
+        PatternSymmetry::RotateAndReflect => {
+            let mut variants = rotations(&pattern).to_vec();
+            variants.extend(rotations(&pattern.flip(Flip::Horizontal)));
+
+            variants
+        }
+    }
+}
+
+/// True if `b`, placed `offset` from `a`, matches `a` everywhere they overlap
+fn patterns_agree<T: PartialEq>(a: &Matrix<T>, b: &Matrix<T>, offset: IVec2) -> bool {
+    let n = a.size().as_ivec2();
+
+    n.positions().into_iter().all(|pos| {
+        let in_b = pos - offset;
+
+        if in_b.min_element() < 0 || !in_b.cmplt(n).all() {
+            return true;
+        }
+
+        a.get(pos.as_uvec2()) == b.get(in_b.as_uvec2())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checkerboard tiles, which can only sit next to the other colour
+    fn checker_rules() -> WfcRules<bool> {
+        let mut rules = WfcRules::new();
+        let black = rules.add_tile(false, 1.0);
+        let white = rules.add_tile(true, 1.0);
+
+        for dir in Dir::ALL_CARDINAL {
+            rules.allow(black, dir, white);
+        }
+
+        rules
+    }
+
+    fn assert_checkerboard(output: &Matrix<bool>) {
+        for (&v, pos) in output.iter_with_pos() {
+            for neighbour in output.neighbours_no_diag(pos) {
+                assert_ne!(v, *output.get(neighbour));
+            }
+        }
+    }
+
+    #[test]
+    fn test_wfc_tiled() {
+        let wfc = WaveFunctionCollapse::new(checker_rules());
+        let output = wfc
+            .generate(
+                UVec2::new(7, 5),
+                &[(UVec2::new(3, 3), true)],
+                &mut Rng::new(),
+            )
+            .unwrap();
+
+        assert_checkerboard(&output);
+        assert!(*output.get(UVec2::new(3, 3)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wfc_zero_weight() {
+        WfcRules::new().add_tile(false, 0.0);
+    }
+
+    #[test]
+    fn test_wfc_contradiction() {
+        let mut wfc = WaveFunctionCollapse::new(checker_rules());
+        wfc.wrapping = true;
+
+        // A checkerboard can't wrap around an odd width
+        let result = wfc.generate(UVec2::new(5, 4), &[], &mut Rng::new());
+        assert!(matches!(result, Err(WfcError::Contradiction(_))));
+
+        let result = wfc.generate(
+            UVec2::new(6, 4),
+            &[(UVec2::new(6, 0), true)],
+            &mut Rng::new(),
+        );
+        assert_eq!(
+            result,
+            Err(WfcError::FixedCellOutOfBounds(UVec2::new(6, 0)))
+        );
+    }
+
+    #[test]
+    fn test_wfc_overlapping() {
+        let sample = Matrix::from_elements_2d(vec![
+            vec![false, true, false, true],
+            vec![true, false, true, false],
+            vec![false, true, false, true],
+            vec![true, false, true, false],
+        ]);
+
+        let rules = WfcRules::from_sample(&sample, 2, PatternSymmetry::RotateAndReflect, true);
+        assert_eq!(rules.len(), 2);
+
+        let mut wfc = WaveFunctionCollapse::new(rules);
+        wfc.wrapping = true;
+
+        let output = wfc
+            .generate(UVec2::new(8, 6), &[], &mut Rng::new())
+            .unwrap();
+
+        assert_checkerboard(&output);
+    }
+}
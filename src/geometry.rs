@@ -0,0 +1,454 @@
+use crate::*;
+
+use std::f64::consts::PI;
+
+/// A filled circle, points on the edge count as inside
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Circle<T: RectScalar> {
+    pub center: T::V2,
+    pub radius: T,
+}
+
+/// A line segment between two points, both ends included
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Segment<T: RectScalar> {
+    pub start: T::V2,
+    pub end: T::V2,
+}
+
+/// A half-line starting at `origin` and extending forever along `direction`
+///
+/// `direction` doesn't need to be normalised, hit times are in multiples of it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray2<T: RectScalar> {
+    pub origin: T::V2,
+    pub direction: T::V2,
+}
+
+/// Where two segments meet
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SegmentIntersection {
+    /// The segments cross or touch at a single point
+    Point(DVec2),
+    /// The segments are collinear and share the stretch between these points
+    Overlap(DVec2, DVec2),
+}
+
+impl<T: RectScalar> Circle<T> {
+    #[inline]
+    pub fn new(center: T::V2, radius: T) -> Self {
+        Self { center, radius }
+    }
+
+    #[inline]
+    pub fn area(&self) -> f64 {
+        PI * self.radius.to_f64() * self.radius.to_f64()
+    }
+
+    #[inline]
+    pub fn contains_point(&self, pos: T::V2) -> bool {
+        T::v2_to_dvec2(self.center).distance_squared(T::v2_to_dvec2(pos))
+            <= self.radius.to_f64() * self.radius.to_f64()
+    }
+
+    #[inline]
+    pub fn intersects_circle(&self, other: &Self) -> bool {
+        let reach = self.radius.to_f64() + other.radius.to_f64();
+
+        T::v2_to_dvec2(self.center).distance_squared(T::v2_to_dvec2(other.center)) <= reach * reach
+    }
+
+    /// Touching the edge of `rect` counts as intersecting, see `covered_region`
+    #[inline]
+    pub fn intersects_rect(&self, rect: &Rect<T>) -> bool {
+        covered_region(rect).is_some() && self.contains_point(rect.clamp_point(self.center))
+    }
+
+    #[inline]
+    pub fn intersects_segment(&self, segment: &Segment<T>) -> bool {
+        segment.distance_to_point(self.center) <= self.radius.to_f64()
+    }
+
+    /// The Rect the circle fits inside, saturating at the scalar's limits
+    ///
+    /// For integer scalars `positions()` include every cell the circle covers
+    pub fn bounding_rect(&self) -> Rect<T> {
+        let center = T::v2_to_dvec2(self.center);
+        let radius = self.radius.to_f64();
+
+        Rect {
+            top_left: T::v2_new(
+                T::from_f64(center.x - radius),
+                T::from_f64(center.y - radius),
+            ),
+            bottom_right: T::v2_new(
+                one_past(T::from_f64(center.x + radius)),
+                one_past(T::from_f64(center.y + radius)),
+            ),
+        }
+    }
+}
+
+impl<T: RectScalar> Segment<T> {
+    #[inline]
+    pub fn new(start: T::V2, end: T::V2) -> Self {
+        Self { start, end }
+    }
+
+    #[inline]
+    pub fn length(&self) -> f64 {
+        T::v2_to_dvec2(self.start).distance(T::v2_to_dvec2(self.end))
+    }
+
+    /// The point on the segment nearest to `pos`
+    pub fn closest_point(&self, pos: T::V2) -> DVec2 {
+        let start = T::v2_to_dvec2(self.start);
+        let delta = T::v2_to_dvec2(self.end) - start;
+        let length_squared = delta.length_squared();
+
+        if length_squared == 0.0 {
+            return start;
+        }
+
+        let t = ((T::v2_to_dvec2(pos) - start).dot(delta) / length_squared).clamp(0.0, 1.0);
+
+        start + delta * t
+    }
+
+    #[inline]
+    pub fn distance_to_point(&self, pos: T::V2) -> f64 {
+        self.closest_point(pos).distance(T::v2_to_dvec2(pos))
+    }
+
+    /// Where `self` and `other` meet, if they do
+    ///
+    /// Collinear segments sharing more than a single point give `SegmentIntersection::Overlap`
+    pub fn intersection(&self, other: &Self) -> Option<SegmentIntersection> {
+        let start = T::v2_to_dvec2(self.start);
+        let delta = T::v2_to_dvec2(self.end) - start;
+        let other_start = T::v2_to_dvec2(other.start);
+        let other_delta = T::v2_to_dvec2(other.end) - other_start;
+        let offset = other_start - start;
+
+        let denominator = delta.perp_dot(other_delta);
+
+        if denominator != 0.0 {
+            let t = offset.perp_dot(other_delta) / denominator;
+            let u = offset.perp_dot(delta) / denominator;
+
+            return ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u))
+                .then(|| SegmentIntersection::Point(start + delta * t));
+        }
+
+        // Parallel, or at least one segment is a single point
+        if offset.perp_dot(delta) != 0.0 || offset.perp_dot(other_delta) != 0.0 {
+            return None;
+        }
+
+        let length_squared = delta.length_squared();
+
+        if length_squared == 0.0 {
+            return (other.distance_to_point(self.start) == 0.0)
+                .then_some(SegmentIntersection::Point(start));
+        }
+
+        // Collinear, project `other` onto `self`
+        let t0 = offset.dot(delta) / length_squared;
+        let t1 = t0 + other_delta.dot(delta) / length_squared;
+        let low = t0.min(t1).max(0.0);
+        let high = t0.max(t1).min(1.0);
+
+        if low > high {
+            None
+        } else if low == high {
+            Some(SegmentIntersection::Point(start + delta * low))
+        } else {
+            Some(SegmentIntersection::Overlap(
+                start + delta * low,
+                start + delta * high,
+            ))
+        }
+    }
+
+    #[inline]
+    pub fn intersects_segment(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Touching the edge of `rect` counts as intersecting, see `covered_region`
+    pub fn intersects_rect(&self, rect: &Rect<T>) -> bool {
+        let start = T::v2_to_dvec2(self.start);
+
+        covered_region(rect)
+            .and_then(|(min, max)| slab_interval(start, T::v2_to_dvec2(self.end) - start, min, max))
+            .is_some_and(|(entry, exit)| entry <= 1.0 && exit >= 0.0)
+    }
+
+    #[inline]
+    pub fn intersects_circle(&self, circle: &Circle<T>) -> bool {
+        circle.intersects_segment(self)
+    }
+
+    /// For integer scalars `positions()` include both ends, saturating at the scalar's limits
+    pub fn bounding_rect(&self) -> Rect<T> {
+        Rect {
+            top_left: T::v2_new(
+                min_scalar(T::v2_x(self.start), T::v2_x(self.end)),
+                min_scalar(T::v2_y(self.start), T::v2_y(self.end)),
+            ),
+            bottom_right: T::v2_new(
+                one_past(max_scalar(T::v2_x(self.start), T::v2_x(self.end))),
+                one_past(max_scalar(T::v2_y(self.start), T::v2_y(self.end))),
+            ),
+        }
+    }
+}
+
+impl<T: RectScalar> Ray2<T> {
+    #[inline]
+    pub fn new(origin: T::V2, direction: T::V2) -> Self {
+        Self { origin, direction }
+    }
+
+    #[inline]
+    pub fn point_at(&self, time: f64) -> DVec2 {
+        T::v2_to_dvec2(self.origin) + T::v2_to_dvec2(self.direction) * time
+    }
+
+    /// Time of the first point on `segment`, in multiples of `direction`
+    pub fn intersect_segment(&self, segment: &Segment<T>) -> Option<f64> {
+        let origin = T::v2_to_dvec2(self.origin);
+        let direction = T::v2_to_dvec2(self.direction);
+        let start = T::v2_to_dvec2(segment.start);
+        let delta = T::v2_to_dvec2(segment.end) - start;
+        let offset = start - origin;
+
+        let denominator = direction.perp_dot(delta);
+
+        if denominator != 0.0 {
+            let t = offset.perp_dot(delta) / denominator;
+            let u = offset.perp_dot(direction) / denominator;
+
+            return (t >= 0.0 && (0.0..=1.0).contains(&u)).then_some(t);
+        }
+
+        let length_squared = direction.length_squared();
+
+        if length_squared == 0.0 || offset.perp_dot(direction) != 0.0 {
+            return None;
+        }
+
+        // Collinear, the nearest end ahead of the ray, or the origin if it's on the segment
+        let t0 = offset.dot(direction) / length_squared;
+        let t1 = t0 + delta.dot(direction) / length_squared;
+        let (low, high) = (t0.min(t1), t0.max(t1));
+
+        (high >= 0.0).then_some(low.max(0.0))
+    }
+
+    /// Time of the first point inside `circle`, 0 if the origin is already inside
+    pub fn intersect_circle(&self, circle: &Circle<T>) -> Option<f64> {
+        if circle.contains_point(self.origin) {
+            return Some(0.0);
+        }
+
+        let direction = T::v2_to_dvec2(self.direction);
+        let offset = T::v2_to_dvec2(self.origin) - T::v2_to_dvec2(circle.center);
+
+        let a = direction.length_squared();
+        let b = offset.dot(direction);
+        let c = offset.length_squared() - circle.radius.to_f64() * circle.radius.to_f64();
+        let discriminant = b * b - a * c;
+
+        if a == 0.0 || discriminant < 0.0 {
+            return None;
+        }
+
+        let t = (-b - discriminant.sqrt()) / a;
+
+        (t >= 0.0).then_some(t)
+    }
+
+    /// Time of the first point inside `rect`, 0 if the origin is already inside, see `covered_region`
+    pub fn intersect_rect(&self, rect: &Rect<T>) -> Option<f64> {
+        covered_region(rect)
+            .and_then(|(min, max)| {
+                slab_interval(
+                    T::v2_to_dvec2(self.origin),
+                    T::v2_to_dvec2(self.direction),
+                    min,
+                    max,
+                )
+            })
+            .and_then(|(entry, exit)| (exit >= 0.0).then_some(entry.max(0.0)))
+    }
+
+    /// Time of the first point inside `polygon`, 0 if the origin is already inside
+    pub fn intersect_polygon(&self, polygon: &Polygon<T>) -> Option<f64> {
+        if polygon.contains_point(self.origin) {
+            return Some(0.0);
+        }
+
+        polygon
+            .edges()
+            .filter_map(|edge| self.intersect_segment(&edge))
+            .min_by(f64::total_cmp)
+    }
+}
+
+/// The closed region the intersection tests treat as `rect`, from `top_left` to `Rect::last_point`
+///
+/// Integer Rects cover the cells of `positions()`, so they end at `bottom_right - 1` and empty ones cover nothing.
+/// Float Rects cover everything between their corners
+pub(crate) fn covered_region<T: RectScalar>(rect: &Rect<T>) -> Option<(DVec2, DVec2)> {
+    if T::IS_INTEGER && rect.is_empty() {
+        return None;
+    }
+
+    Some((
+        T::v2_to_dvec2(rect.top_left),
+        T::v2_to_dvec2(rect.last_point()),
+    ))
+}
+
+/// `bottom_right` for a Rect whose `positions()` include `value`, unchanged for floats
+#[inline]
+pub(crate) fn one_past<T: RectScalar>(value: T) -> T {
+    if T::IS_INTEGER && value < T::max() {
+        value + T::one()
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circle_intersections() {
+        let circle = Circle::<i32>::new(IVec2::new(5, 5), 2);
+
+        assert!(circle.contains_point(IVec2::new(7, 5)));
+        assert!(!circle.contains_point(IVec2::new(7, 7)));
+
+        assert!(circle.intersects_circle(&Circle::new(IVec2::new(9, 5), 2)));
+        assert!(!circle.intersects_circle(&Circle::new(IVec2::new(10, 5), 2)));
+
+        assert!(circle.intersects_rect(&Rect::from_corners(IVec2::new(7, 0), IVec2::new(9, 9))));
+        assert!(!circle.intersects_rect(&Rect::from_corners(IVec2::new(7, 7), IVec2::new(9, 9))));
+        // The last column of cells is x = 2, 3 away from the center
+        assert!(!circle.intersects_rect(&Rect::from_corners(IVec2::new(1, 1), IVec2::new(3, 9))));
+        assert!(!circle.intersects_rect(&Rect::from_corners(IVec2::new(5, 5), IVec2::new(5, 9))));
+
+        assert!(circle.intersects_segment(&Segment::new(IVec2::new(0, 3), IVec2::new(10, 3))));
+        assert!(!circle.intersects_segment(&Segment::new(IVec2::new(0, 2), IVec2::new(10, 2))));
+
+        assert_eq!(
+            Circle::<u32>::new(UVec2::new(1, 5), 3).bounding_rect(),
+            Rect::from_corners(UVec2::new(0, 2), UVec2::new(5, 9))
+        );
+    }
+
+    #[test]
+    fn test_segment_intersections() {
+        let diagonal = Segment::<f32>::new(Vec2::ZERO, Vec2::splat(4.0));
+
+        assert_eq!(
+            diagonal.intersection(&Segment::new(Vec2::new(0.0, 4.0), Vec2::new(4.0, 0.0))),
+            Some(SegmentIntersection::Point(DVec2::splat(2.0)))
+        );
+        assert_eq!(
+            diagonal.intersection(&Segment::new(Vec2::new(0.0, 1.0), Vec2::new(3.0, 4.0))),
+            None
+        );
+        assert_eq!(
+            diagonal.intersection(&Segment::new(Vec2::splat(6.0), Vec2::splat(2.0))),
+            Some(SegmentIntersection::Overlap(
+                DVec2::splat(2.0),
+                DVec2::splat(4.0)
+            ))
+        );
+        assert_eq!(
+            diagonal.intersection(&Segment::new(Vec2::splat(4.0), Vec2::splat(5.0))),
+            Some(SegmentIntersection::Point(DVec2::splat(4.0)))
+        );
+
+        let rect = Rect::from_corners(Vec2::new(3.0, 0.0), Vec2::new(5.0, 1.0));
+        assert!(!diagonal.intersects_rect(&rect));
+        assert!(Segment::new(Vec2::new(0.0, 1.0), Vec2::new(6.0, 0.0)).intersects_rect(&rect));
+        assert!(Segment::new(Vec2::new(4.0, 0.5), Vec2::new(4.0, 0.5)).intersects_rect(&rect));
+
+        assert_eq!(
+            diagonal.distance_to_point(Vec2::new(4.0, 0.0)),
+            8.0f64.sqrt()
+        );
+        let segment = Segment::<i32>::new(IVec2::new(3, -1), IVec2::new(-2, 4));
+        assert_eq!(
+            segment.bounding_rect(),
+            Rect::from_corners(IVec2::new(-2, -1), IVec2::new(4, 5))
+        );
+        assert!(segment.bounding_rect().contains_pos(segment.start));
+        assert!(segment.bounding_rect().contains_pos(segment.end));
+
+        // Integer Rects end at their last column of cells, the same as for circles
+        let cells = Rect::<i32>::from_corners(IVec2::new(1, 1), IVec2::new(3, 9));
+        assert!(Segment::new(IVec2::new(2, 0), IVec2::new(2, 10)).intersects_rect(&cells));
+        assert!(!Segment::new(IVec2::new(3, 0), IVec2::new(3, 10)).intersects_rect(&cells));
+        assert!(!Circle::new(IVec2::new(4, 5), 1).intersects_rect(&cells));
+        assert_eq!(
+            Ray2::new(IVec2::new(3, 0), IVec2::new(0, 1)).intersect_rect(&cells),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ray_intersections() {
+        let ray = Ray2::<f32>::new(Vec2::ZERO, Vec2::new(1.0, 0.0));
+
+        assert_eq!(
+            ray.intersect_segment(&Segment::new(Vec2::new(3.0, -1.0), Vec2::new(3.0, 1.0))),
+            Some(3.0)
+        );
+        assert_eq!(
+            ray.intersect_segment(&Segment::new(Vec2::new(-3.0, -1.0), Vec2::new(-3.0, 1.0))),
+            None
+        );
+        assert_eq!(
+            ray.intersect_segment(&Segment::new(Vec2::new(5.0, 0.0), Vec2::new(2.0, 0.0))),
+            Some(2.0)
+        );
+
+        assert_eq!(
+            ray.intersect_circle(&Circle::new(Vec2::new(5.0, 0.0), 1.0)),
+            Some(4.0)
+        );
+        assert_eq!(
+            ray.intersect_circle(&Circle::new(Vec2::new(5.0, 2.0), 1.0)),
+            None
+        );
+        assert_eq!(
+            ray.intersect_circle(&Circle::new(Vec2::ZERO, 1.0)),
+            Some(0.0)
+        );
+
+        let rect = Rect::from_corners(Vec2::new(2.0, -1.0), Vec2::new(4.0, 1.0));
+        assert_eq!(ray.intersect_rect(&rect), Some(2.0));
+        assert_eq!(
+            Ray2::new(Vec2::new(3.0, 0.0), Vec2::new(1.0, 0.0)).intersect_rect(&rect),
+            Some(0.0)
+        );
+        assert_eq!(
+            Ray2::new(Vec2::new(5.0, 0.0), Vec2::new(1.0, 0.0)).intersect_rect(&rect),
+            None
+        );
+
+        let triangle = Polygon::<f32>::new(vec![
+            Vec2::new(6.0, -2.0),
+            Vec2::new(8.0, 2.0),
+            Vec2::new(4.0, 2.0),
+        ]);
+        assert_eq!(ray.intersect_polygon(&triangle), Some(5.0));
+        assert_eq!(ray.point_at(5.0), DVec2::new(5.0, 0.0));
+    }
+}
@@ -1,23 +1,47 @@
+mod bounds;
+mod closed_rect;
+mod collision;
 // mod direction;
 mod dir;
+mod generation;
+mod geometry;
 mod matrix_module;
+mod oriented_rect;
+mod packing;
+mod point_graph;
+mod polygon;
+mod position_iterators;
 mod rect;
+mod rect_layout;
 mod rect_scalar;
 // mod rectangle;
+mod simplify;
 mod vec2_traits;
 
+pub use bounds::*;
+pub use closed_rect::*;
+pub use collision::*;
 // pub use direction::*;
 pub use dir::*;
+pub use generation::*;
+pub use geometry::*;
 pub use matrix_module::*;
+pub use oriented_rect::*;
+pub use packing::*;
+pub use point_graph::*;
+pub use polygon::*;
+pub use position_iterators::*;
 pub use rect::*;
+pub use rect_layout::*;
 pub use rect_scalar::*;
 // pub use rectangle::*;
+pub use simplify::*;
 pub use vec2_traits::*;
 
 pub(crate) use rng::*;
 
 #[cfg(not(feature = "bevy"))]
-pub use glam::{IVec2, UVec2, Vec2};
+pub use glam::{DVec2, I64Vec2, IVec2, U16Vec2, UVec2, Vec2};
 #[cfg(not(feature = "bevy"))]
 pub use hashbrown::{HashMap, HashSet};
 
@@ -29,7 +53,7 @@ pub(crate) use std::hint::unreachable_unchecked;
 pub(crate) use bevy::prelude::Reflect;
 #[cfg(feature = "bevy")]
 pub use bevy::{
-    math::{IVec2, UVec2, Vec2},
+    math::{DVec2, I64Vec2, IVec2, U16Vec2, UVec2, Vec2},
     platform::collections::{HashMap, HashSet},
 };
 
@@ -56,3 +80,11 @@ pub(crate) fn wrap_i32(value: i32, range: Range<i32>) -> i32 {
 
     ((value - range.start) % size + size) % size + range.start
 }
+
+/// Fisher-Yates shuffle
+pub(crate) fn shuffle<T>(values: &mut [T], rng: &mut Rng) {
+    for i in (1..values.len()).rev() {
+        let j = rng.gen_range(0..i + 1);
+        values.swap(i, j);
+    }
+}
@@ -236,8 +236,8 @@ impl<T> Matrix<T> {
         let mut neighbours = Vec::new();
 
         for dir in dirs {
-            let new_x = wrap_i32(pos_i.x + dir.x, 0..size_i.max_element());
-            let new_y = wrap_i32(pos_i.y + dir.y, 0..size_i.max_element());
+            let new_x = wrap_i32(pos_i.x + dir.x, 0..size_i.x);
+            let new_y = wrap_i32(pos_i.y + dir.y, 0..size_i.y);
 
             let new = UVec2::new(new_x as u32, new_y as u32);
 
@@ -262,8 +262,8 @@ impl<T> Matrix<T> {
         let mut neighbours = Vec::new();
 
         for dir in dirs {
-            let new_x = wrap_i32(pos_i.x + dir.x, 0..size_i.max_element());
-            let new_y = wrap_i32(pos_i.y + dir.y, 0..size_i.max_element());
+            let new_x = wrap_i32(pos_i.x + dir.x, 0..size_i.x);
+            let new_y = wrap_i32(pos_i.y + dir.y, 0..size_i.y);
 
             let new = UVec2::new(new_x as u32, new_y as u32);
 
@@ -286,8 +286,8 @@ impl<T> Matrix<T> {
         let mut neighbours = Vec::new();
 
         for dir in dirs {
-            let new_x = wrap_i32(pos_i.x + dir.x, 0..size_i.max_element());
-            let new_y = wrap_i32(pos_i.y + dir.y, 0..size_i.max_element());
+            let new_x = wrap_i32(pos_i.x + dir.x, 0..size_i.x);
+            let new_y = wrap_i32(pos_i.y + dir.y, 0..size_i.y);
 
             let new = UVec2::new(new_x as u32, new_y as u32);
 
@@ -403,3 +403,46 @@ impl<T> Matrix<T> {
         positions
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbours_wrapping_non_square() {
+        let matrix = Matrix::splat(UVec2::new(5, 3), 0_u8);
+        let corner = UVec2::new(4, 0);
+
+        assert_eq!(
+            matrix.neighbours_wrapping(corner),
+            [
+                UVec2::new(4, 2),
+                UVec2::new(4, 1),
+                UVec2::new(3, 0),
+                UVec2::new(0, 0),
+                UVec2::new(3, 2),
+                UVec2::new(0, 2),
+                UVec2::new(3, 1),
+                UVec2::new(0, 1),
+            ]
+        );
+        assert_eq!(
+            matrix.neighbours_no_diag_wrapping(UVec2::new(0, 2)),
+            [
+                UVec2::new(0, 1),
+                UVec2::new(0, 0),
+                UVec2::new(4, 2),
+                UVec2::new(1, 2),
+            ]
+        );
+        assert_eq!(
+            matrix.neighbours_diag_only_wrapping(corner),
+            [
+                UVec2::new(3, 2),
+                UVec2::new(0, 2),
+                UVec2::new(3, 1),
+                UVec2::new(0, 1),
+            ]
+        );
+    }
+}
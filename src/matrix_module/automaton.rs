@@ -0,0 +1,460 @@
+use crate::*;
+
+use core::{fmt, str::FromStr};
+
+/// A rule for stepping every cell of a `Matrix` at once
+pub trait CellularAutomaton {
+    type Cell: Clone + PartialEq;
+
+    /// The state of the cell at `pos` in the next generation
+    ///
+    /// `neighbours` are the current states of the cells around `pos`,
+    /// in the order returned by the matching `Matrix` neighbour function
+    fn next_state(
+        &mut self,
+        cell: &Self::Cell,
+        pos: UVec2,
+        neighbours: &[&Self::Cell],
+    ) -> Self::Cell;
+}
+
+/// Steps a `Matrix` with a `CellularAutomaton` rule
+///
+/// Double-buffered, so stepping never allocates a new matrix
+#[derive(Clone, Debug)]
+pub struct AutomatonEngine<A: CellularAutomaton> {
+    pub automaton: A,
+    /// `Eight` = Moore neighbourhood, `Four` = von Neumann neighbourhood
+    pub connectivity: Connectivity,
+    /// Neighbourhoods wrap around the edges of the matrix (torus)
+    pub wrapping: bool,
+    /// Only processes cells that changed in the last step, and their neighbours.
+    ///
+    /// Much faster for sparse patterns, but only valid for rules where a cell can't change
+    /// unless it or a neighbour changed (no randomness)
+    pub only_active: bool,
+    current: Matrix<A::Cell>,
+    next: Matrix<A::Cell>,
+    active: Vec<UVec2>,
+    is_active: BitMatrix,
+    /// Reused by `step` so it doesn't allocate
+    to_process: Vec<UVec2>,
+    changed: Vec<UVec2>,
+    generation: u64,
+}
+
+impl<A: CellularAutomaton> AutomatonEngine<A> {
+    pub fn new(automaton: A, cells: Matrix<A::Cell>, connectivity: Connectivity) -> Self {
+        let size = cells.size();
+
+        let mut is_active = BitMatrix::new(size);
+        let active = size.positions();
+
+        for &pos in &active {
+            is_active.set(pos, true);
+        }
+
+        Self {
+            automaton,
+            connectivity,
+            wrapping: false,
+            only_active: false,
+            next: cells.clone(),
+            current: cells,
+            active,
+            is_active,
+            to_process: Vec::new(),
+            changed: Vec::new(),
+            generation: 0,
+        }
+    }
+
+    #[inline]
+    pub fn cells(&self) -> &Matrix<A::Cell> {
+        &self.current
+    }
+
+    #[inline]
+    pub fn into_cells(self) -> Matrix<A::Cell> {
+        self.current
+    }
+
+    /// Number of steps run so far
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Sets a cell between steps, marking it and its neighbours as active
+    pub fn set(&mut self, pos: UVec2, value: A::Cell) {
+        self.current.set(pos, value.clone());
+        self.next.set(pos, value);
+
+        self.activate_around(pos);
+    }
+
+    /// Advances one generation
+    ///
+    /// Returns the number of cells that changed
+    pub fn step(&mut self) -> u32 {
+        let mut to_process = core::mem::take(&mut self.to_process);
+        let mut changed = core::mem::take(&mut self.changed);
+        to_process.clear();
+        changed.clear();
+
+        if self.only_active {
+            to_process.append(&mut self.active);
+        } else {
+            self.active.clear();
+            to_process.extend(self.current.size().iter_positions());
+        }
+
+        for &pos in &to_process {
+            self.is_active.set(pos, false);
+        }
+
+        let mut neighbour_cells = Vec::with_capacity(8);
+        let size = self.current.size();
+
+        for &pos in &to_process {
+            neighbour_cells.clear();
+            neighbour_cells.extend(
+                neighbours(pos, size, self.connectivity, self.wrapping)
+                    .map(|neighbour| self.current.get(neighbour)),
+            );
+
+            let cell = self.current.get(pos);
+            let new_cell = self.automaton.next_state(cell, pos, &neighbour_cells);
+
+            if new_cell != *cell {
+                changed.push(pos);
+            }
+
+            self.next.set(pos, new_cell);
+        }
+
+        core::mem::swap(&mut self.current, &mut self.next);
+
+        // Bring the old buffer up to date, so unprocessed cells stay correct next step
+        for &pos in &changed {
+            self.next.set(pos, self.current.get(pos).clone());
+            self.activate_around(pos);
+        }
+
+        self.generation += 1;
+
+        let changed_count = changed.len() as u32;
+        self.to_process = to_process;
+        self.changed = changed;
+
+        changed_count
+    }
+
+    /// Steps until nothing changes, or `max_steps` is reached
+    ///
+    /// Returns the number of steps run
+    pub fn run_until_stable(&mut self, max_steps: u32) -> u32 {
+        for steps in 0..max_steps {
+            if self.step() == 0 {
+                return steps + 1;
+            }
+        }
+
+        max_steps
+    }
+
+    fn activate_around(&mut self, pos: UVec2) {
+        let size = self.current.size();
+
+        for p in neighbours(pos, size, self.connectivity, self.wrapping).chain([pos]) {
+            if self.is_active.insert(p) {
+                self.active.push(p);
+            }
+        }
+    }
+}
+
+/// Offsets in the order of `Matrix::neighbours` and `Matrix::neighbours_no_diag`
+const OFFSETS_EIGHT: [IVec2; 8] = [
+    IVec2::new(-1, -1),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 0),
+    IVec2::new(-1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(1, 1),
+];
+const OFFSETS_FOUR: [IVec2; 4] = [
+    IVec2::new(0, -1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 0),
+    IVec2::new(0, 1),
+];
+
+/// Offsets in the order of `Matrix::neighbours_wrapping` and `Matrix::neighbours_no_diag_wrapping`
+const OFFSETS_EIGHT_WRAPPING: [IVec2; 8] = [
+    IVec2::new(0, -1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 0),
+    IVec2::new(-1, -1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(1, 1),
+];
+const OFFSETS_FOUR_WRAPPING: [IVec2; 4] = [
+    IVec2::new(0, -1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(1, 0),
+];
+
+/// Same positions and order as the matching `Matrix` neighbour function, without allocating
+#[inline]
+fn neighbours(
+    pos: UVec2,
+    size: UVec2,
+    connectivity: Connectivity,
+    wrapping: bool,
+) -> impl Iterator<Item = UVec2> {
+    let offsets: &'static [IVec2] = match (connectivity, wrapping) {
+        (Connectivity::Four, false) => &OFFSETS_FOUR,
+        (Connectivity::Four, true) => &OFFSETS_FOUR_WRAPPING,
+        (Connectivity::Eight, false) => &OFFSETS_EIGHT,
+        (Connectivity::Eight, true) => &OFFSETS_EIGHT_WRAPPING,
+    };
+
+    let pos = pos.as_ivec2();
+    let size = size.as_ivec2();
+
+    offsets.iter().filter_map(move |&offset| {
+        let neighbour = pos + offset;
+
+        if wrapping {
+            Some(UVec2::new(
+                wrap_i32(neighbour.x, 0..size.x) as u32,
+                wrap_i32(neighbour.y, 0..size.y) as u32,
+            ))
+        } else {
+            (neighbour.cmpge(IVec2::ZERO).all() && neighbour.cmplt(size).all())
+                .then(|| neighbour.as_uvec2())
+        }
+    })
+}
+
+/// Life-like rule (eg. Conway's Game of Life) on `bool` cells, `true` = alive
+///
+/// `birth[n]` / `survival[n]` are true if a dead / alive cell with `n` live neighbours is alive next generation
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    pub birth: [bool; 9],
+    pub survival: [bool; 9],
+}
+
+impl LifeRule {
+    /// B3/S23
+    pub const CONWAY: Self = Self::from_counts(&[3], &[2, 3]);
+    /// B36/S23
+    pub const HIGH_LIFE: Self = Self::from_counts(&[3, 6], &[2, 3]);
+    /// B3/S12345
+    pub const MAZE: Self = Self::from_counts(&[3], &[1, 2, 3, 4, 5]);
+
+    /// Panics if a count is above 8
+    pub const fn from_counts(birth: &[u8], survival: &[u8]) -> Self {
+        let mut rule = Self {
+            birth: [false; 9],
+            survival: [false; 9],
+        };
+
+        let mut i = 0;
+        while i < birth.len() {
+            rule.birth[birth[i] as usize] = true;
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < survival.len() {
+            rule.survival[survival[i] as usize] = true;
+            i += 1;
+        }
+
+        rule
+    }
+}
+
+impl CellularAutomaton for LifeRule {
+    type Cell = bool;
+
+    #[inline]
+    fn next_state(&mut self, &cell: &bool, _pos: UVec2, neighbours: &[&bool]) -> bool {
+        let alive = neighbours.iter().filter(|&&&v| v).count();
+
+        if cell {
+            self.survival[alive]
+        } else {
+            self.birth[alive]
+        }
+    }
+}
+
+/// Parses rule strings in B/S notation, eg. "B3/S23"
+///
+/// The parts can be in either order and are case-insensitive, eg. "s23/b3"
+impl FromStr for LifeRule {
+    type Err = RuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut birth = None;
+        let mut survival = None;
+
+        for part in s.trim().split('/') {
+            let mut chars = part.trim().chars();
+
+            let counts = match chars.next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') if birth.is_none() => birth.insert([false; 9]),
+                Some('S') if survival.is_none() => survival.insert([false; 9]),
+                _ => return Err(RuleParseError::InvalidPart(part.to_string())),
+            };
+
+            for c in chars {
+                match c.to_digit(10) {
+                    Some(n) if n <= 8 => counts[n as usize] = true,
+                    _ => return Err(RuleParseError::InvalidNeighbourCount(c)),
+                }
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(Self { birth, survival }),
+            (None, _) => Err(RuleParseError::MissingBirth),
+            (_, None) => Err(RuleParseError::MissingSurvival),
+        }
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9)
+                .filter(|&n| counts[n])
+                .map(|n| char::from(b'0' + n as u8))
+                .collect()
+        };
+
+        write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleParseError {
+    MissingBirth,
+    MissingSurvival,
+    /// A part that doesn't start with B or S, or a repeated part
+    InvalidPart(String),
+    /// Neighbour counts must be digits from 0 to 8
+    InvalidNeighbourCount(char),
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingBirth => write!(f, "rule has no birth (B) part"),
+            Self::MissingSurvival => write!(f, "rule has no survival (S) part"),
+            Self::InvalidPart(part) => write!(f, "invalid rule part \"{part}\""),
+            Self::InvalidNeighbourCount(c) => write!(f, "invalid neighbour count '{c}'"),
+        }
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blinker() -> Matrix<bool> {
+        let mut cells = Matrix::splat(UVec2::splat(5), false);
+
+        for x in 1..4 {
+            cells.set(UVec2::new(x, 2), true);
+        }
+
+        cells
+    }
+
+    #[test]
+    fn test_parse_life_rule() {
+        assert_eq!("B3/S23".parse(), Ok(LifeRule::CONWAY));
+        assert_eq!("s23/b36".parse(), Ok(LifeRule::HIGH_LIFE));
+        assert_eq!(LifeRule::MAZE.to_string(), "B3/S12345");
+
+        assert_eq!(
+            "B3".parse::<LifeRule>(),
+            Err(RuleParseError::MissingSurvival)
+        );
+        assert_eq!(
+            "B39/S23".parse::<LifeRule>(),
+            Err(RuleParseError::InvalidNeighbourCount('9'))
+        );
+    }
+
+    #[test]
+    fn test_neighbours_match_matrix() {
+        let matrix = Matrix::splat(UVec2::new(4, 3), false);
+
+        for pos in matrix.size().positions() {
+            for (connectivity, wrapping, expected) in [
+                (Connectivity::Four, false, matrix.neighbours_no_diag(pos)),
+                (
+                    Connectivity::Four,
+                    true,
+                    matrix.neighbours_no_diag_wrapping(pos),
+                ),
+                (Connectivity::Eight, false, matrix.neighbours(pos)),
+                (Connectivity::Eight, true, matrix.neighbours_wrapping(pos)),
+            ] {
+                assert_eq!(
+                    neighbours(pos, matrix.size(), connectivity, wrapping).collect_vec(),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_blinker_oscillates() {
+        for only_active in [false, true] {
+            let mut engine = AutomatonEngine::new(LifeRule::CONWAY, blinker(), Connectivity::Eight);
+            engine.only_active = only_active;
+
+            assert_eq!(engine.step(), 4);
+            assert!(*engine.cells().get(UVec2::new(2, 1)));
+            assert!(!*engine.cells().get(UVec2::new(1, 2)));
+
+            engine.step();
+            assert_eq!(engine.cells(), &blinker());
+            assert_eq!(engine.generation(), 2);
+        }
+    }
+
+    #[test]
+    fn test_glider_wraps() {
+        let mut cells = Matrix::splat(UVec2::new(6, 8), false);
+
+        for pos in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            cells.set(UVec2::new(pos.0, pos.1), true);
+        }
+
+        let mut engine = AutomatonEngine::new(LifeRule::CONWAY, cells.clone(), Connectivity::Eight);
+        engine.wrapping = true;
+        engine.only_active = true;
+
+        // A glider moves one cell diagonally every 4 generations, so returns after 4 * 24 (lcm of 6 and 8)
+        for _ in 0..96 {
+            engine.step();
+        }
+
+        assert_eq!(engine.cells(), &cells);
+    }
+}
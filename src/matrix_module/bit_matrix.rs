@@ -0,0 +1,84 @@
+use crate::*;
+
+/// A grid of flags packed into 64-bit words
+///
+/// Useful as a visited map for searches, much faster than a `HashSet<UVec2>` on large matrices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    words: Vec<u64>,
+    size: UVec2,
+}
+
+impl BitMatrix {
+    pub fn new(size: UVec2) -> Self {
+        let word_count = (size.element_product() as usize).div_ceil(64);
+
+        Self {
+            words: vec![0; word_count],
+            size,
+        }
+    }
+
+    #[inline]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    #[inline(always)]
+    fn idx(&self, pos: UVec2) -> usize {
+        debug_assert!(
+            pos.is_in_bounds(self.size),
+            "Position {} is out of bounds for bit matrix of size {}",
+            pos,
+            self.size
+        );
+
+        (pos.y * self.size.x + pos.x) as usize
+    }
+
+    #[inline(always)]
+    pub fn get(&self, pos: UVec2) -> bool {
+        let idx = self.idx(pos);
+
+        self.words[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, pos: UVec2, value: bool) {
+        let idx = self.idx(pos);
+        let mask = 1 << (idx % 64);
+
+        if value {
+            self.words[idx / 64] |= mask;
+        } else {
+            self.words[idx / 64] &= !mask;
+        }
+    }
+
+    /// Sets the flag at `pos`
+    ///
+    /// Returns true if the flag was not already set
+    #[inline(always)]
+    pub fn insert(&mut self, pos: UVec2) -> bool {
+        let idx = self.idx(pos);
+        let mask = 1 << (idx % 64);
+        let word = &mut self.words[idx / 64];
+        let was_set = *word & mask != 0;
+
+        *word |= mask;
+
+        !was_set
+    }
+
+    /// Unsets every flag
+    #[inline]
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Number of set flags
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.words.iter().map(|word| word.count_ones()).sum()
+    }
+}
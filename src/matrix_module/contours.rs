@@ -0,0 +1,355 @@
+use crate::*;
+
+/// A closed loop of boundary cells
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contour {
+    /// Boundary cells in tracing order, the last cell connects back to the first
+    pub points: Vec<UVec2>,
+    /// True if this contour is the inner boundary of a hole
+    pub is_hole: bool,
+}
+
+impl Matrix<bool> {
+    /// Traces the boundaries of all `true` regions (8-connected)
+    ///
+    /// Uses Suzuki-Abe border following, which walks each boundary with Moore-neighbour tracing.
+    /// Outer contours are traced anti-clockwise, hole contours clockwise (with y pointing down).
+    pub fn trace_contours(&self) -> Vec<Contour> {
+        let size = self.size();
+        // Padded with a background border so tracing never leaves the grid
+        let width = size.x as i32 + 2;
+        let height = size.y as i32 + 2;
+
+        let mut labels = vec![0_i32; (width * height) as usize];
+
+        for (&v, pos) in self.iter_with_pos() {
+            if v {
+                labels[((pos.y as i32 + 1) * width + pos.x as i32 + 1) as usize] = 1;
+            }
+        }
+
+        let idx = |p: IVec2| (p.y * width + p.x) as usize;
+
+        let mut contours = Vec::new();
+        let mut border_id = 1;
+
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                let pos = IVec2::new(x, y);
+                let value = labels[idx(pos)];
+
+                let start = if value == 1 && labels[idx(pos - IVec2::X)] == 0 {
+                    Some((pos - IVec2::X, false))
+                } else if value >= 1 && labels[idx(pos + IVec2::X)] == 0 {
+                    Some((pos + IVec2::X, true))
+                } else {
+                    None
+                };
+
+                let Some((from, is_hole)) = start else {
+                    continue;
+                };
+
+                border_id += 1;
+
+                let points = follow_border(&mut labels, width, pos, from, border_id);
+
+                contours.push(Contour {
+                    points: points
+                        .into_iter()
+                        .map(|p| (p - IVec2::ONE).as_uvec2())
+                        .collect(),
+                    is_hole,
+                });
+            }
+        }
+
+        contours
+    }
+}
+
+/// Follows a single border starting at `start`, with `from` being the background cell it was entered from
+fn follow_border(
+    labels: &mut [i32],
+    width: i32,
+    start: IVec2,
+    from: IVec2,
+    border_id: i32,
+) -> Vec<IVec2> {
+    let idx = |p: IVec2| (p.y * width + p.x) as usize;
+    let dir_to = |a: IVec2, b: IVec2| Dir::from_vector(b - a).unwrap();
+
+    // Search clockwise around the start for the first non-background cell
+    let from_dir = dir_to(start, from);
+    let first = (0..8)
+        .map(|step| start + from_dir.turn(step).to_vector())
+        .find(|&p| labels[idx(p)] != 0);
+
+    let Some(first) = first else {
+        // Isolated cell
+        labels[idx(start)] = -border_id;
+
+        return vec![start];
+    };
+
+    let mut points = Vec::new();
+    let mut previous = first;
+    let mut current = start;
+
+    loop {
+        points.push(current);
+
+        // Search anti-clockwise, starting after the previous cell
+        let previous_dir = dir_to(current, previous);
+        let mut east_examined_empty = false;
+        let mut next = current;
+
+        for step in 1..=8 {
+            let dir = previous_dir.turn(-step);
+            let p = current + dir.to_vector();
+
+            if labels[idx(p)] != 0 {
+                next = p;
+                break;
+            }
+
+            if dir == Dir::E {
+                east_examined_empty = true;
+            }
+        }
+
+        if east_examined_empty {
+            labels[idx(current)] = -border_id;
+        } else if labels[idx(current)] == 1 {
+            labels[idx(current)] = border_id;
+        }
+
+        if next == start && current == first {
+            break;
+        }
+
+        previous = current;
+        current = next;
+    }
+
+    points
+}
+
+impl Matrix<f32> {
+    /// Extracts iso-lines at `iso_level` using marching squares
+    ///
+    /// Each cell value is treated as a sample at its position, so output coordinates are in cell space.
+    /// Crossing points are linearly interpolated along the edges between samples.
+    /// Closed loops repeat their first point at the end.
+    pub fn marching_squares(&self, iso_level: f32) -> Vec<Vec<Vec2>> {
+        let size = self.size();
+
+        if size.x < 2 || size.y < 2 {
+            return Vec::new();
+        }
+
+        // Each segment joins two sample edges, edges are shared between neighbouring squares
+        let mut segments: Vec<[EdgeId; 2]> = Vec::new();
+
+        for y in 0..size.y - 1 {
+            for x in 0..size.x - 1 {
+                let tl = *self.get(UVec2::new(x, y));
+                let tr = *self.get(UVec2::new(x + 1, y));
+                let br = *self.get(UVec2::new(x + 1, y + 1));
+                let bl = *self.get(UVec2::new(x, y + 1));
+
+                let case = (tl >= iso_level) as u8
+                    | ((tr >= iso_level) as u8) << 1
+                    | ((br >= iso_level) as u8) << 2
+                    | ((bl >= iso_level) as u8) << 3;
+
+                let top = EdgeId::Horizontal(x, y);
+                let bottom = EdgeId::Horizontal(x, y + 1);
+                let left = EdgeId::Vertical(x, y);
+                let right = EdgeId::Vertical(x + 1, y);
+
+                let center_inside = (tl + tr + br + bl) / 4.0 >= iso_level;
+
+                match case {
+                    1 | 14 => segments.push([left, top]),
+                    2 | 13 => segments.push([top, right]),
+                    3 | 12 => segments.push([left, right]),
+                    4 | 11 => segments.push([right, bottom]),
+                    6 | 9 => segments.push([top, bottom]),
+                    7 | 8 => segments.push([left, bottom]),
+                    // Saddles, resolved using the average of the corners
+                    5 if center_inside => segments.extend([[top, right], [bottom, left]]),
+                    5 => segments.extend([[left, top], [right, bottom]]),
+                    10 if center_inside => segments.extend([[left, top], [right, bottom]]),
+                    10 => segments.extend([[top, right], [bottom, left]]),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut edge_segments: HashMap<EdgeId, Vec<usize>> = HashMap::new();
+
+        for (i, segment) in segments.iter().enumerate() {
+            for edge in segment {
+                edge_segments.entry(*edge).or_default().push(i);
+            }
+        }
+
+        let mut used = vec![false; segments.len()];
+        let mut lines = Vec::new();
+
+        // Open lines must start at an edge only touched once, so walk those first
+        let open_starts = segments
+            .iter()
+            .enumerate()
+            .flat_map(|(i, segment)| segment.iter().map(move |edge| (i, *edge)))
+            .filter(|(_, edge)| edge_segments[edge].len() == 1)
+            .collect_vec();
+        let closed_starts = (0..segments.len()).map(|i| (i, segments[i][0]));
+
+        for (first_segment, start_edge) in open_starts.into_iter().chain(closed_starts) {
+            if used[first_segment] {
+                continue;
+            }
+
+            let mut edges = vec![start_edge];
+            let mut segment = first_segment;
+            let mut edge = start_edge;
+
+            loop {
+                used[segment] = true;
+
+                let [a, b] = segments[segment];
+                edge = if a == edge { b } else { a };
+                edges.push(edge);
+
+                let next = edge_segments[&edge].iter().find(|&&s| !used[s]);
+
+                match next {
+                    Some(&s) => segment = s,
+                    None => break,
+                }
+            }
+
+            lines.push(
+                edges
+                    .into_iter()
+                    .map(|edge| self.edge_crossing(edge, iso_level))
+                    .collect(),
+            );
+        }
+
+        lines
+    }
+
+    /// Interpolated position where `iso_level` crosses the edge
+    fn edge_crossing(&self, edge: EdgeId, iso_level: f32) -> Vec2 {
+        let (a, b) = match edge {
+            EdgeId::Horizontal(x, y) => (UVec2::new(x, y), UVec2::new(x + 1, y)),
+            EdgeId::Vertical(x, y) => (UVec2::new(x, y), UVec2::new(x, y + 1)),
+        };
+
+        let value_a = *self.get(a);
+        let value_b = *self.get(b);
+
+        let t = if value_a == value_b {
+            0.5
+        } else {
+            ((iso_level - value_a) / (value_b - value_a)).clamp(0.0, 1.0)
+        };
+
+        a.as_vec2().lerp(b.as_vec2(), t)
+    }
+}
+
+/// An edge between two neighbouring samples, identified by its top/left sample
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum EdgeId {
+    Horizontal(u32, u32),
+    Vertical(u32, u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const Y: bool = true;
+    const N: bool = false;
+
+    #[test]
+    fn test_trace_contours_with_hole() {
+        let matrix = Matrix::from_elements_2d(vec![
+            vec![N, N, N, N, N],
+            vec![N, Y, Y, Y, N],
+            vec![N, Y, N, Y, N],
+            vec![N, Y, Y, Y, N],
+            vec![N, N, N, N, N],
+        ]);
+
+        let contours = matrix.trace_contours();
+
+        assert_eq!(contours.len(), 2);
+
+        let outer = &contours[0];
+        assert!(!outer.is_hole);
+        assert_eq!(outer.points.len(), 8);
+        assert_eq!(outer.points[0], UVec2::new(1, 1));
+        assert_eq!(outer.points[1], UVec2::new(1, 2));
+
+        let hole = &contours[1];
+        assert!(hole.is_hole);
+        // Diagonal steps are allowed, so the corners of the hole are skipped
+        assert_eq!(
+            hole.points,
+            vec![
+                UVec2::new(1, 2),
+                UVec2::new(2, 1),
+                UVec2::new(3, 2),
+                UVec2::new(2, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_contours_single_cells() {
+        let matrix = Matrix::from_elements_2d(vec![vec![Y, N, N], vec![N, N, N], vec![N, N, Y]]);
+
+        let contours = matrix.trace_contours();
+
+        assert_eq!(contours.len(), 2);
+        assert_eq!(contours[0].points, vec![UVec2::new(0, 0)]);
+        assert_eq!(contours[1].points, vec![UVec2::new(2, 2)]);
+    }
+
+    #[test]
+    fn test_marching_squares_closed_loop() {
+        let matrix = Matrix::from_elements_2d(vec![
+            vec![0.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 0.0],
+        ]);
+
+        let lines = matrix.marching_squares(0.5);
+
+        assert_eq!(lines.len(), 1);
+
+        let line = &lines[0];
+        assert_eq!(line.len(), 5);
+        assert_eq!(line.first(), line.last());
+
+        for point in line {
+            assert!((point.distance(Vec2::ONE) - 0.5).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_marching_squares_open_line() {
+        let matrix = Matrix::from_elements_2d(vec![vec![0.0, 1.0], vec![0.0, 1.0]]);
+
+        let lines = matrix.marching_squares(0.25);
+
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), 2);
+        assert!(lines[0].iter().all(|p| (p.x - 0.25).abs() < 0.0001));
+    }
+}
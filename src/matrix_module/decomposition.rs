@@ -0,0 +1,256 @@
+use crate::*;
+
+/// Strategy used to cover cells with rectangles
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RectDecomposition {
+    /// Greedy meshing: grows each rect right along its row, then down while the whole run matches.
+    /// Very fast, but can produce more rects than necessary
+    Greedy,
+    /// Repeatedly takes the largest remaining rect.
+    /// Much slower on large matrices
+    LargestFirst,
+    /// Runs every other strategy (and greedy meshing down columns first) and keeps the result with the fewest rects.
+    /// Not guaranteed to be optimal, but never worse than `Greedy`
+    Fewest,
+}
+
+impl Matrix<bool> {
+    /// Covers every `true` cell with non-overlapping rects
+    ///
+    /// Rects are exclusive (`bottom_right` is one past the last cell), matching `Rect::positions()`
+    pub fn decompose_rects(&self, mode: RectDecomposition) -> Vec<Rect<u32>> {
+        match mode {
+            RectDecomposition::Greedy => self.greedy_rects(Orientation::Horizontal),
+            RectDecomposition::LargestFirst => self.largest_first_rects(),
+            RectDecomposition::Fewest => [
+                self.greedy_rects(Orientation::Horizontal),
+                self.greedy_rects(Orientation::Vertical),
+                self.largest_first_rects(),
+            ]
+            .into_iter()
+            .min_by_key(|rects| rects.len())
+            .unwrap(),
+        }
+    }
+
+    /// Largest rect containing only `false` cells
+    ///
+    /// Returns None if every cell is `true`
+    #[inline]
+    pub fn largest_empty_rect(&self) -> Option<Rect<u32>> {
+        self.largest_rect_matching(|&v, _| !v)
+    }
+
+    /// `orientation` is the direction each rect grows first, Horizontal = along rows
+    fn greedy_rects(&self, orientation: Orientation) -> Vec<Rect<u32>> {
+        let size = self.size();
+        let mut covered = BitMatrix::new(size);
+        let mut rects = Vec::new();
+
+        // Swaps x and y so the same code can grow down columns first
+        let flip = |pos: UVec2| match orientation {
+            Orientation::Vertical => UVec2::new(pos.y, pos.x),
+            _ => pos,
+        };
+        let flipped_size = flip(size);
+
+        let is_free = |pos: UVec2, covered: &BitMatrix| {
+            let pos = flip(pos);
+
+            *self.get(pos) && !covered.get(pos)
+        };
+
+        for pos in flipped_size.positions() {
+            if !is_free(pos, &covered) {
+                continue;
+            }
+
+            let mut end_x = pos.x + 1;
+
+            while end_x < flipped_size.x && is_free(UVec2::new(end_x, pos.y), &covered) {
+                end_x += 1;
+            }
+
+            let mut end_y = pos.y + 1;
+
+            while end_y < flipped_size.y
+                && (pos.x..end_x).all(|x| is_free(UVec2::new(x, end_y), &covered))
+            {
+                end_y += 1;
+            }
+
+            let rect: Rect<u32> = Rect::from_corners(flip(pos), flip(UVec2::new(end_x, end_y)));
+
+            for p in rect.positions() {
+                covered.set(p, true);
+            }
+
+            rects.push(rect);
+        }
+
+        rects
+    }
+
+    fn largest_first_rects(&self) -> Vec<Rect<u32>> {
+        let mut remaining = self.clone();
+        let mut rects = Vec::new();
+
+        while let Some(rect) = remaining.largest_rect_matching(|&v, _| v) {
+            for pos in rect.positions() {
+                remaining.set(pos, false);
+            }
+
+            rects.push(rect);
+        }
+
+        rects
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Largest rect (by area) where every cell matches `match_fn`
+    ///
+    /// Uses the maximal rectangle in a histogram method, O(n) in the number of cells.
+    /// The rect is exclusive (`bottom_right` is one past the last cell)
+    pub fn largest_rect_matching(&self, match_fn: impl Fn(&T, UVec2) -> bool) -> Option<Rect<u32>> {
+        let size = self.size();
+
+        // Number of consecutive matching cells above and including the current row
+        let mut heights = vec![0_u32; size.x as usize];
+        let mut stack: Vec<usize> = Vec::with_capacity(size.x as usize);
+
+        let mut best: Option<(u32, Rect<u32>)> = None;
+
+        for y in 0..size.y {
+            for (x, height) in heights.iter_mut().enumerate() {
+                let pos = UVec2::new(x as u32, y);
+
+                if match_fn(self.get(pos), pos) {
+                    *height += 1;
+                } else {
+                    *height = 0;
+                }
+            }
+
+            stack.clear();
+
+            // An extra zero height bar at the end flushes the stack
+            for x in 0..=heights.len() {
+                let current = heights.get(x).copied().unwrap_or(0);
+
+                while let Some(&top) = stack.last() {
+                    if heights[top] < current {
+                        break;
+                    }
+
+                    stack.pop();
+
+                    let height = heights[top];
+                    let left = stack.last().map_or(0, |&l| l + 1);
+                    let width = (x - left) as u32;
+                    let area = width * height;
+
+                    if height > 0 && best.is_none_or(|(best_area, _)| area > best_area) {
+                        let rect = Rect::from_corners(
+                            UVec2::new(left as u32, y + 1 - height),
+                            UVec2::new(x as u32, y + 1),
+                        );
+
+                        best = Some((area, rect));
+                    }
+                }
+
+                stack.push(x);
+            }
+        }
+
+        best.map(|(_, rect)| rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const Y: bool = true;
+    const N: bool = false;
+
+    fn example() -> Matrix<bool> {
+        Matrix::from_elements_2d(vec![
+            vec![Y, Y, N, N, N],
+            vec![Y, Y, Y, Y, N],
+            vec![Y, Y, Y, Y, N],
+            vec![N, N, Y, Y, N],
+            vec![N, N, N, N, N],
+        ])
+    }
+
+    fn assert_exact_cover(matrix: &Matrix<bool>, rects: &[Rect<u32>]) {
+        let mut covered = Matrix::splat(matrix.size(), false);
+
+        for rect in rects {
+            for pos in rect.positions() {
+                assert!(*matrix.get(pos), "{pos} is not a true cell");
+                assert!(!*covered.get(pos), "{pos} is covered twice");
+
+                covered.set(pos, true);
+            }
+        }
+
+        assert_eq!(&covered, matrix);
+    }
+
+    #[test]
+    fn test_decompose_rects_greedy() {
+        let matrix = example();
+        let rects = matrix.decompose_rects(RectDecomposition::Greedy);
+
+        assert_exact_cover(&matrix, &rects);
+        assert_eq!(rects.len(), 2);
+    }
+
+    #[test]
+    fn test_decompose_rects_largest_first() {
+        let matrix = example();
+        let rects = matrix.decompose_rects(RectDecomposition::LargestFirst);
+
+        assert_exact_cover(&matrix, &rects);
+        assert_eq!(rects.len(), 3);
+        assert_eq!(
+            rects[0],
+            Rect::from_corners(UVec2::new(0, 1), UVec2::new(4, 3))
+        );
+    }
+
+    #[test]
+    fn test_decompose_rects_fewest() {
+        let matrix = Matrix::from_elements_2d(vec![
+            vec![Y, Y, Y, Y],
+            vec![Y, N, N, Y],
+            vec![Y, N, N, Y],
+            vec![Y, Y, Y, Y],
+        ]);
+
+        let greedy = matrix.decompose_rects(RectDecomposition::Greedy);
+        let fewest = matrix.decompose_rects(RectDecomposition::Fewest);
+
+        assert_exact_cover(&matrix, &fewest);
+        assert!(fewest.len() <= greedy.len());
+        assert_eq!(fewest.len(), 4);
+    }
+
+    #[test]
+    fn test_largest_empty_rect() {
+        let matrix = example();
+
+        assert_eq!(
+            matrix.largest_empty_rect(),
+            Some(Rect::from_corners(UVec2::new(4, 0), UVec2::new(5, 5)))
+        );
+
+        assert_eq!(
+            Matrix::splat(UVec2::splat(3), true).largest_empty_rect(),
+            None
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use crate::*;
+
+use core::f32::consts::SQRT_2;
+
+impl<T> Matrix<T> {
+    /// Approximate euclidean distance from each cell to the nearest obstacle cell
+    ///
+    /// Uses a two-pass chamfer transform with weights of 1 (cardinal) and √2 (diagonal).
+    /// Obstacle cells have a distance of 0, and cells next to an obstacle have a distance of 1.
+    ///
+    /// ## Arguments
+    /// * `is_obstacle` - Returns true for cells distances are measured to
+    /// * `border_is_obstacle` - Treats every cell outside the matrix as an obstacle
+    ///
+    /// Cells that can't reach any obstacle are set to `f32::INFINITY`
+    pub fn distance_transform(
+        &self,
+        is_obstacle: impl Fn(&T, UVec2) -> bool,
+        border_is_obstacle: bool,
+    ) -> Matrix<f32> {
+        let size = self.size().as_ivec2();
+        let initial = self
+            .iter_with_pos()
+            .map(|(v, pos)| {
+                if is_obstacle(v, pos) {
+                    0.0
+                } else {
+                    f32::INFINITY
+                }
+            })
+            .collect();
+        let mut distances = Matrix::from_elements(initial, self.size());
+
+        let outside = if border_is_obstacle {
+            0.0
+        } else {
+            f32::INFINITY
+        };
+
+        // (offset, weight) pairs already visited by each pass
+        let forward = [
+            (IVec2::new(-1, -1), SQRT_2),
+            (IVec2::new(0, -1), 1.0),
+            (IVec2::new(1, -1), SQRT_2),
+            (IVec2::new(-1, 0), 1.0),
+        ];
+        let backward = forward.map(|(offset, weight)| (-offset, weight));
+
+        let mut relax = |pos: IVec2, offsets: &[(IVec2, f32); 4]| {
+            let current = *distances.get(pos.as_uvec2());
+
+            let best = offsets.iter().fold(current, |best, &(offset, weight)| {
+                let neighbour = pos + offset;
+
+                let dist = if neighbour.cmpge(IVec2::ZERO).all() && neighbour.cmplt(size).all() {
+                    *distances.get(neighbour.as_uvec2())
+                } else {
+                    outside
+                };
+
+                best.min(dist + weight)
+            });
+
+            distances.set(pos.as_uvec2(), best);
+        };
+
+        for y in 0..size.y {
+            for x in 0..size.x {
+                relax(IVec2::new(x, y), &forward);
+            }
+        }
+
+        for y in (0..size.y).rev() {
+            for x in (0..size.x).rev() {
+                relax(IVec2::new(x, y), &backward);
+            }
+        }
+
+        distances
+    }
+}
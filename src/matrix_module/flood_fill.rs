@@ -0,0 +1,215 @@
+use crate::*;
+
+/// Which neighbouring cells count as connected
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// Cardinal neighbours only (von Neumann neighbourhood)
+    Four,
+    /// Cardinal and diagonal neighbours (Moore neighbourhood)
+    Eight,
+}
+
+/// Summary of a flood fill
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FloodFillResult {
+    /// Number of cells that were filled
+    pub count: u32,
+    /// Bounds of the filled cells (`bottom_right` is exclusive)
+    pub bounds: Rect<u32>,
+}
+
+impl<T> Matrix<T> {
+    /// Sets every cell connected to `seed` where `predicate` returns true to `new_value`
+    ///
+    /// Returns None if `seed` is out of bounds or doesn't match `predicate`
+    pub fn flood_fill(
+        &mut self,
+        seed: UVec2,
+        predicate: impl Fn(&T, UVec2) -> bool,
+        new_value: T,
+        connectivity: Connectivity,
+    ) -> Option<FloodFillResult>
+    where
+        T: Clone,
+    {
+        self.flood_fill_with(seed, predicate, |v| *v = new_value.clone(), connectivity)
+    }
+
+    /// Calls `fill_fn` on every cell connected to `seed` where `predicate` returns true
+    ///
+    /// Uses a scanline (span) fill, so each row segment is only pushed to the stack once.
+    /// `predicate` is always called on the original value, so `fill_fn` can produce values that would still match.
+    ///
+    /// Returns None if `seed` is out of bounds or doesn't match `predicate`
+    pub fn flood_fill_with(
+        &mut self,
+        seed: UVec2,
+        predicate: impl Fn(&T, UVec2) -> bool,
+        mut fill_fn: impl FnMut(&mut T),
+        connectivity: Connectivity,
+    ) -> Option<FloodFillResult> {
+        if !self.is_in_bounds(seed) || !predicate(self.get(seed), seed) {
+            return None;
+        }
+
+        let size = self.size();
+        let mut visited = BitMatrix::new(size);
+        let mut stack = vec![seed];
+
+        let mut count = 0;
+        let mut min = seed;
+        let mut max = seed;
+
+        // Cells diagonal to a span's ends are connected when using 8-connectivity
+        let reach = match connectivity {
+            Connectivity::Four => 0,
+            Connectivity::Eight => 1,
+        };
+
+        while let Some(pos) = stack.pop() {
+            if visited.get(pos) {
+                continue;
+            }
+
+            let y = pos.y;
+            let mut left = pos.x;
+            let mut right = pos.x;
+
+            while left > 0 {
+                let next = UVec2::new(left - 1, y);
+
+                if visited.get(next) || !predicate(self.get(next), next) {
+                    break;
+                }
+
+                left -= 1;
+            }
+
+            while right + 1 < size.x {
+                let next = UVec2::new(right + 1, y);
+
+                if visited.get(next) || !predicate(self.get(next), next) {
+                    break;
+                }
+
+                right += 1;
+            }
+
+            for x in left..=right {
+                let p = UVec2::new(x, y);
+
+                visited.set(p, true);
+                fill_fn(self.get_mut(p));
+            }
+
+            count += right - left + 1;
+            min = min.min(UVec2::new(left, y));
+            max = max.max(UVec2::new(right, y));
+
+            let scan_start = left.saturating_sub(reach);
+            let scan_end = (right + reach).min(size.x - 1);
+
+            let rows = [y.checked_sub(1), (y + 1 < size.y).then_some(y + 1)];
+
+            for row in rows.into_iter().flatten() {
+                let mut in_span = false;
+
+                for x in scan_start..=scan_end {
+                    let p = UVec2::new(x, row);
+
+                    if !visited.get(p) && predicate(self.get(p), p) {
+                        // Only the first cell of each run needs to be pushed
+                        if !in_span {
+                            stack.push(p);
+                            in_span = true;
+                        }
+                    } else {
+                        in_span = false;
+                    }
+                }
+            }
+        }
+
+        Some(FloodFillResult {
+            count,
+            bounds: Rect::from_corners(min, max + UVec2::ONE),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const Y: bool = true;
+    const N: bool = false;
+
+    #[test]
+    fn test_flood_fill_four_connectivity() {
+        let mut matrix = Matrix::from_elements_2d(vec![
+            vec![Y, Y, N, N],
+            vec![N, Y, N, Y],
+            vec![N, Y, Y, N],
+            vec![Y, N, N, N],
+        ]);
+
+        let result = matrix
+            .flood_fill(UVec2::ZERO, |&v, _| v, false, Connectivity::Four)
+            .unwrap();
+
+        assert_eq!(result.count, 5);
+        assert_eq!(
+            result.bounds,
+            Rect::from_corners(UVec2::new(0, 0), UVec2::new(3, 3))
+        );
+
+        // Diagonally connected cells are untouched
+        assert!(*matrix.get(UVec2::new(3, 1)));
+        assert!(*matrix.get(UVec2::new(0, 3)));
+        assert_eq!(matrix.count_matches(|&v, _| v), 2);
+    }
+
+    #[test]
+    fn test_flood_fill_eight_connectivity() {
+        let mut matrix = Matrix::from_elements_2d(vec![
+            vec![Y, Y, N, N],
+            vec![N, Y, N, Y],
+            vec![N, Y, Y, N],
+            vec![Y, N, N, N],
+        ]);
+
+        let result = matrix
+            .flood_fill(UVec2::ZERO, |&v, _| v, false, Connectivity::Eight)
+            .unwrap();
+
+        assert_eq!(result.count, 7);
+        assert_eq!(matrix.count_matches(|&v, _| v), 0);
+    }
+
+    #[test]
+    fn test_flood_fill_with_matching_value() {
+        // The new value still matches the predicate, the visited map must stop it refilling
+        let mut matrix = Matrix::splat(UVec2::splat(16), 0_u32);
+
+        let result = matrix
+            .flood_fill_with(
+                UVec2::new(5, 5),
+                |_, _| true,
+                |v| *v += 1,
+                Connectivity::Four,
+            )
+            .unwrap();
+
+        assert_eq!(result.count, 256);
+        assert!(matrix.iter().all(|&v| v == 1));
+    }
+
+    #[test]
+    fn test_flood_fill_seed_not_matching() {
+        let mut matrix = Matrix::splat(UVec2::splat(4), false);
+
+        let result = matrix.flood_fill(UVec2::ZERO, |&v, _| v, true, Connectivity::Four);
+
+        assert!(result.is_none());
+    }
+}
@@ -1,13 +1,29 @@
 mod algorithms;
 mod analysis;
+mod automaton;
+mod bit_matrix;
+mod contours;
+mod decomposition;
+mod distance_transform;
+mod flood_fill;
 mod iterators;
 mod matrix;
+mod polygons;
+mod regions;
 mod scaling;
 mod transformations;
+mod voronoi;
 
 pub use algorithms::*;
 pub use analysis::*;
+pub use automaton::*;
+pub use bit_matrix::*;
+pub use contours::*;
+pub use decomposition::*;
+pub use flood_fill::*;
 pub use iterators::*;
 pub use matrix::*;
+pub use regions::*;
 pub use scaling::*;
 pub use transformations::*;
+pub use voronoi::*;
@@ -0,0 +1,207 @@
+use crate::*;
+
+impl Matrix<bool> {
+    /// Converts all `true` regions into axis-aligned polygons
+    ///
+    /// Polygon corners lie on cell corners, so a single cell at (x, y) becomes the square (x, y) -> (x + 1, y + 1).
+    /// Regions are 4-connected, cells only touching diagonally become separate polygons.
+    ///
+    /// Exteriors wind clockwise and holes anti-clockwise (with y pointing down), collinear points are merged.
+    pub fn to_polygons(&self) -> Vec<Polygon<i32>> {
+        let loops = self.trace_cell_edges();
+
+        let mut exteriors = Vec::new();
+        let mut holes = Vec::new();
+
+        for (points, inside_point) in loops {
+            let area = signed_area(&points);
+
+            if area > 0 {
+                exteriors.push((Polygon::new(points), area));
+            } else {
+                holes.push((points, inside_point));
+            }
+        }
+
+        for (hole, inside_point) in holes {
+            // The filled cell next to the hole is inside the exterior of the same region,
+            // and any other exterior containing it must be larger
+            let owner = exteriors
+                .iter_mut()
+                .filter(|(polygon, _)| loop_contains_point(&polygon.exterior, inside_point))
+                .min_by_key(|(_, area)| *area);
+
+            if let Some((polygon, _)) = owner {
+                polygon.holes.push(hole);
+            }
+        }
+
+        exteriors.into_iter().map(|(polygon, _)| polygon).collect()
+    }
+
+    /// Follows the edges between `true` and `false` cells, with the `true` cell always on the right
+    ///
+    /// Returns each loop along with the center of a `true` cell bordering it
+    fn trace_cell_edges(&self) -> Vec<(Vec<IVec2>, Vec2)> {
+        let size = self.size();
+        let is_filled = |pos: IVec2| {
+            pos.min_element() >= 0
+                && Self::is_in_bounds_multi(pos.as_uvec2(), size)
+                && *self.get(pos.as_uvec2())
+        };
+
+        // Edges are stored by their start corner and direction
+        let mut edges: HashSet<(IVec2, Dir)> = HashSet::new();
+
+        for (&v, pos) in self.iter_with_pos() {
+            if !v {
+                continue;
+            }
+
+            let cell = pos.as_ivec2();
+
+            for dir in Dir::ALL_CARDINAL {
+                if !is_filled(cell + dir.to_vector()) {
+                    edges.insert(cell_edge(cell, dir));
+                }
+            }
+        }
+
+        let mut loops = Vec::new();
+
+        // Iterate in cell order so output is deterministic
+        for (&v, pos) in self.iter_with_pos() {
+            if !v {
+                continue;
+            }
+
+            for dir in Dir::ALL_CARDINAL {
+                let start = cell_edge(pos.as_ivec2(), dir);
+
+                if !edges.remove(&start) {
+                    continue;
+                }
+
+                let mut points = vec![start.0];
+                let (mut corner, mut heading) = start;
+
+                loop {
+                    corner += heading.to_vector();
+
+                    // Prefer turning right to stay on the same cell, which keeps diagonal regions separate
+                    let next = [heading.turn_right_90(), heading, heading.turn_left_90()]
+                        .into_iter()
+                        .map(|d| (corner, d))
+                        .find(|&edge| edge == start || edges.remove(&edge));
+
+                    match next {
+                        Some(edge) if edge != start => {
+                            points.push(corner);
+                            heading = edge.1;
+                        }
+                        _ => break,
+                    }
+                }
+
+                let inside_point = pos.as_vec2() + Vec2::splat(0.5);
+
+                loops.push((points.merge_collinear(true), inside_point));
+            }
+        }
+
+        loops
+    }
+}
+
+/// The edge of `cell` facing `side`, directed clockwise around the cell
+#[inline]
+fn cell_edge(cell: IVec2, side: Dir) -> (IVec2, Dir) {
+    match side {
+        Dir::N => (cell, Dir::E),
+        Dir::E => (cell + IVec2::new(1, 0), Dir::S),
+        Dir::S => (cell + IVec2::new(1, 1), Dir::W),
+        Dir::W => (cell + IVec2::new(0, 1), Dir::N),
+        _ => unreachable!("Cell edges must be cardinal"),
+    }
+}
+
+/// Twice the signed area of a closed loop, positive when clockwise (with y pointing down)
+#[inline]
+fn signed_area(points: &[IVec2]) -> i64 {
+    points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+        .sum()
+}
+
+/// Even-odd point in polygon test
+#[inline]
+fn loop_contains_point(points: &[IVec2], point: Vec2) -> bool {
+    let mut inside = false;
+
+    for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+        let (a, b) = (a.as_vec2(), b.as_vec2());
+
+        if (a.y > point.y) != (b.y > point.y) {
+            let crossing_x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+
+            if point.x < crossing_x {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const Y: bool = true;
+    const N: bool = false;
+
+    #[test]
+    fn test_to_polygons_square_with_hole() {
+        let matrix = Matrix::from_elements_2d(vec![
+            vec![Y, Y, Y, N],
+            vec![Y, N, Y, N],
+            vec![Y, Y, Y, N],
+            vec![N, N, N, Y],
+        ]);
+
+        let polygons = matrix.to_polygons();
+
+        assert_eq!(polygons.len(), 2);
+
+        assert_eq!(
+            polygons[0],
+            Polygon::with_holes(
+                vec![
+                    IVec2::new(0, 0),
+                    IVec2::new(3, 0),
+                    IVec2::new(3, 3),
+                    IVec2::new(0, 3),
+                ],
+                vec![vec![
+                    IVec2::new(2, 1),
+                    IVec2::new(1, 1),
+                    IVec2::new(1, 2),
+                    IVec2::new(2, 2),
+                ]],
+            )
+        );
+
+        // Only touches the first region diagonally
+        assert_eq!(
+            polygons[1],
+            Polygon::new(vec![
+                IVec2::new(3, 3),
+                IVec2::new(4, 3),
+                IVec2::new(4, 4),
+                IVec2::new(3, 4),
+            ])
+        );
+    }
+}
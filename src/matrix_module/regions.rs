@@ -0,0 +1,364 @@
+use crate::*;
+
+impl<T> Matrix<T> {
+    /// Labels each connected region of cells matching `predicate` with a unique id
+    ///
+    /// Labels start at 1, cells not matching `predicate` are labelled 0.
+    ///
+    /// Returns the label matrix and the number of regions
+    pub fn label_regions(
+        &self,
+        predicate: impl Fn(&T, UVec2) -> bool,
+        connectivity: Connectivity,
+    ) -> (Matrix<u32>, u32) {
+        const UNLABELLED: u32 = u32::MAX;
+
+        let mut labels = Matrix::from_elements(
+            self.iter_with_pos()
+                .map(|(v, pos)| if predicate(v, pos) { UNLABELLED } else { 0 })
+                .collect(),
+            self.size(),
+        );
+
+        let mut region_count = 0;
+
+        for pos in self.positions() {
+            if *labels.get(pos) == UNLABELLED {
+                region_count += 1;
+
+                labels.flood_fill(pos, |&v, _| v == UNLABELLED, region_count, connectivity);
+            }
+        }
+
+        (labels, region_count)
+    }
+}
+
+/// A region in a `RegionGraph`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionNode {
+    pub label: u32,
+    pub cell_count: u32,
+    /// Exclusive bounds
+    pub bounds: Rect<u32>,
+}
+
+/// Two regions with directly touching (cardinal) cells
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionEdge {
+    /// The smaller of the two labels
+    pub a: u32,
+    /// The larger of the two labels
+    pub b: u32,
+    /// Cells from both regions that touch the other region
+    pub border_cells: Vec<UVec2>,
+    /// Size of the opening between the regions,
+    /// the smaller of the border cell counts on each side
+    pub width: u32,
+}
+
+/// Which regions of a label matrix touch each other
+///
+/// Label 0 is treated as no region (walls, void etc.)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionGraph {
+    /// Sorted by label
+    pub nodes: Vec<RegionNode>,
+    /// Sorted by (a, b)
+    pub edges: Vec<RegionEdge>,
+}
+
+impl RegionGraph {
+    pub fn from_labels(labels: &Matrix<u32>) -> Self {
+        let size = labels.size();
+
+        let mut nodes: HashMap<u32, RegionNode> = HashMap::new();
+        // Border cells of each side, keyed by (a, b) with a < b
+        let mut borders: HashMap<(u32, u32), (HashSet<UVec2>, HashSet<UVec2>)> = HashMap::new();
+
+        for (&label, pos) in labels.iter_with_pos() {
+            if label == 0 {
+                continue;
+            }
+
+            nodes
+                .entry(label)
+                .and_modify(|node| {
+                    node.cell_count += 1;
+                    node.bounds.top_left = node.bounds.top_left.min(pos);
+                    node.bounds.bottom_right = node.bounds.bottom_right.max(pos + UVec2::ONE);
+                })
+                .or_insert_with(|| RegionNode {
+                    label,
+                    cell_count: 1,
+                    bounds: Rect::from_top_left_and_size(pos, UVec2::ONE),
+                });
+
+            // Only look forwards so each pair of cells is checked once
+            let forward = [UVec2::new(pos.x + 1, pos.y), UVec2::new(pos.x, pos.y + 1)];
+
+            for neighbour in forward {
+                if !Matrix::<u32>::is_in_bounds_multi(neighbour, size) {
+                    continue;
+                }
+
+                let other = *labels.get(neighbour);
+
+                if other == 0 || other == label {
+                    continue;
+                }
+
+                let (key, a_cell, b_cell) = if label < other {
+                    ((label, other), pos, neighbour)
+                } else {
+                    ((other, label), neighbour, pos)
+                };
+
+                let (a_cells, b_cells) = borders.entry(key).or_default();
+                a_cells.insert(a_cell);
+                b_cells.insert(b_cell);
+            }
+        }
+
+        let nodes = nodes
+            .into_values()
+            .sorted_by_key(|node| node.label)
+            .collect();
+
+        let edges = borders
+            .into_iter()
+            .map(|((a, b), (a_cells, b_cells))| {
+                let width = a_cells.len().min(b_cells.len()) as u32;
+                let border_cells = a_cells
+                    .into_iter()
+                    .chain(b_cells)
+                    .sorted_by_key(|pos| (pos.y, pos.x))
+                    .collect();
+
+                RegionEdge {
+                    a,
+                    b,
+                    border_cells,
+                    width,
+                }
+            })
+            .sorted_by_key(|edge| (edge.a, edge.b))
+            .collect();
+
+        Self { nodes, edges }
+    }
+
+    #[inline]
+    pub fn node(&self, label: u32) -> Option<&RegionNode> {
+        self.nodes
+            .binary_search_by_key(&label, |node| node.label)
+            .ok()
+            .map(|idx| &self.nodes[idx])
+    }
+
+    #[inline]
+    pub fn edge(&self, a: u32, b: u32) -> Option<&RegionEdge> {
+        let key = (a.min(b), a.max(b));
+
+        self.edges
+            .binary_search_by_key(&key, |edge| (edge.a, edge.b))
+            .ok()
+            .map(|idx| &self.edges[idx])
+    }
+
+    /// Labels of all regions touching `label`
+    pub fn neighbours(&self, label: u32) -> Vec<u32> {
+        self.edges
+            .iter()
+            .filter_map(|edge| {
+                if edge.a == label {
+                    Some(edge.b)
+                } else if edge.b == label {
+                    Some(edge.a)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// The narrowest point of a narrow passage
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Chokepoint {
+    pub pos: UVec2,
+    /// Approximate width of the passage in cells
+    pub width: f32,
+    /// Label of the region containing `pos`
+    pub region: u32,
+}
+
+impl Matrix<u32> {
+    /// Finds narrow passages no wider than `max_width`, treating label 0 as walls
+    ///
+    /// A cell is part of a passage when it lies on a ridge of the distance transform:
+    /// its distance to the nearest wall is greater than both neighbours on one side,
+    /// but no greater than both neighbours along the passage.
+    /// One chokepoint is returned for each connected group of passage cells, at its narrowest cell
+    pub fn chokepoints(&self, max_width: f32) -> Vec<Chokepoint> {
+        let size = self.size();
+        let distances = self.distance_transform(|&label, _| label == 0, true);
+
+        let dist_at = |pos: IVec2| {
+            if pos.min_element() < 0 || !Self::is_in_bounds_multi(pos.as_uvec2(), size) {
+                0.0
+            } else {
+                *distances.get(pos.as_uvec2())
+            }
+        };
+
+        // Width of the passage through a cell, measured from the walls on either side
+        let width_at = |dist: f32| dist * 2.0 - 1.0;
+
+        let mut is_passage = BitMatrix::new(size);
+
+        for (&dist, pos) in distances.iter_with_pos() {
+            if dist == 0.0 || width_at(dist) > max_width {
+                continue;
+            }
+
+            let p = pos.as_ivec2();
+
+            let is_saddle = [Dir::N, Dir::NE, Dir::E, Dir::SE]
+                .into_iter()
+                .any(|across| {
+                    let along = across.turn_right_90();
+
+                    let across_lower = dist_at(p + across.to_vector()) < dist
+                        && dist_at(p + across.opposite().to_vector()) < dist;
+                    let along_not_lower = dist_at(p + along.to_vector()) >= dist
+                        && dist_at(p + along.opposite().to_vector()) >= dist;
+
+                    across_lower && along_not_lower
+                });
+
+            if is_saddle {
+                is_passage.set(pos, true);
+            }
+        }
+
+        let mut visited = BitMatrix::new(size);
+        let mut chokepoints = Vec::new();
+
+        for pos in size.positions() {
+            if !is_passage.get(pos) || !visited.insert(pos) {
+                continue;
+            }
+
+            // Collect the connected group of passage cells
+            let mut group = vec![pos];
+            let mut stack = vec![pos];
+
+            while let Some(current) = stack.pop() {
+                for neighbour in self.neighbours(current) {
+                    if is_passage.get(neighbour) && visited.insert(neighbour) {
+                        group.push(neighbour);
+                        stack.push(neighbour);
+                    }
+                }
+            }
+
+            // Narrowest cell, ties broken by distance to the middle of the group
+            let center = group.iter().map(|p| p.as_vec2()).sum::<Vec2>() / group.len() as f32;
+
+            let narrowest = group
+                .iter()
+                .copied()
+                .min_by(|&a, &b| {
+                    let key = |p: UVec2| (*distances.get(p), p.as_vec2().distance(center));
+
+                    key(a).partial_cmp(&key(b)).unwrap()
+                })
+                .unwrap();
+
+            chokepoints.push(Chokepoint {
+                pos: narrowest,
+                width: width_at(*distances.get(narrowest)),
+                region: *self.get(narrowest),
+            });
+        }
+
+        chokepoints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two 5x5 rooms joined by a 1 wide corridor
+    fn two_rooms() -> Matrix<bool> {
+        let mut walkable = Matrix::splat(UVec2::new(13, 7), false);
+
+        let rooms: [Rect<u32>; 2] = [
+            Rect::from_corners(UVec2::new(1, 1), UVec2::new(6, 6)),
+            Rect::from_corners(UVec2::new(7, 1), UVec2::new(12, 6)),
+        ];
+
+        for room in rooms {
+            for pos in room.positions() {
+                walkable.set(pos, true);
+            }
+        }
+
+        walkable.set(UVec2::new(6, 3), true);
+
+        walkable
+    }
+
+    #[test]
+    fn test_label_regions() {
+        let mut walkable = two_rooms();
+
+        let (_, count) = walkable.label_regions(|&v, _| v, Connectivity::Four);
+        assert_eq!(count, 1);
+
+        walkable.set(UVec2::new(6, 3), false);
+
+        let (labels, count) = walkable.label_regions(|&v, _| v, Connectivity::Four);
+        assert_eq!(count, 2);
+        assert_eq!(*labels.get(UVec2::new(0, 0)), 0);
+        assert_eq!(*labels.get(UVec2::new(1, 1)), 1);
+        assert_eq!(*labels.get(UVec2::new(11, 5)), 2);
+    }
+
+    #[test]
+    fn test_region_graph() {
+        let walkable = two_rooms();
+
+        // Split the corridor off from both rooms
+        let (mut labels, _) = walkable.label_regions(|&v, _| v, Connectivity::Four);
+        labels.set(UVec2::new(6, 3), 2);
+        for pos in Rect::<u32>::from_corners(UVec2::new(7, 1), UVec2::new(12, 6)).positions() {
+            labels.set(pos, 3);
+        }
+
+        let graph = RegionGraph::from_labels(&labels);
+
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.node(2).unwrap().cell_count, 1);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.neighbours(2), vec![1, 3]);
+        assert!(graph.edge(3, 1).is_none());
+
+        let edge = graph.edge(2, 1).unwrap();
+        assert_eq!(edge.width, 1);
+        assert_eq!(edge.border_cells, vec![UVec2::new(5, 3), UVec2::new(6, 3)]);
+    }
+
+    #[test]
+    fn test_chokepoints() {
+        let (labels, _) = two_rooms().label_regions(|&v, _| v, Connectivity::Four);
+
+        let chokepoints = labels.chokepoints(1.0);
+
+        assert_eq!(chokepoints.len(), 1);
+        assert_eq!(chokepoints[0].pos, UVec2::new(6, 3));
+        assert_eq!(chokepoints[0].width, 1.0);
+    }
+}
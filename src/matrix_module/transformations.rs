@@ -123,63 +123,54 @@ impl<T: Clone + Debug> Matrix<T> {
     //     Matrix::from_inner(new_inner)
     // }
 
-    // /// Returns a copy of the matrix, rotated in the specified direction
-    // #[inline]
-    // #[must_use]
-    // pub fn rotate(&self, direction: Rotation) -> Matrix<T> {
-    //     let rows = self.get_inner().len();
-    //     if rows == 0 {
-    //         return Matrix::from_inner(Vec::new());
-    //     }
-    //     let cols = self.get_inner()[0].len();
+    /// Returns a copy of the matrix, rotated in the specified direction
+    #[inline]
+    #[must_use]
+    pub fn rotate(&self, direction: MatrixRotation) -> Matrix<T> {
+        let size = self.size();
+        let new_size = match direction {
+            MatrixRotation::D180 => size,
+            _ => UVec2::new(size.y, size.x),
+        };
+
+        let elements = new_size
+            .positions()
+            .into_iter()
+            .map(|pos| {
+                let source = match direction {
+                    MatrixRotation::D90 => UVec2::new(pos.y, size.y - 1 - pos.x),
+                    MatrixRotation::D180 => size - UVec2::ONE - pos,
+                    MatrixRotation::D270 => UVec2::new(size.x - 1 - pos.y, pos.x),
+                };
+
+                self.get(source).clone()
+            })
+            .collect();
 
-    //     let mut result = vec![vec![self.get_inner()[0][0].clone(); rows]; cols];
+        Matrix::from_elements(elements, new_size)
+    }
 
-    //     match direction {
-    //         Rotation::D90 => {
-    //             for (i, row) in self.get_inner().iter().enumerate() {
-    //                 for (j, val) in row.iter().enumerate() {
-    //                     result[j][rows - 1 - i] = val.clone();
-    //                 }
-    //             }
-    //         }
-    //         Rotation::D180 => {
-    //             for (i, row) in self.get_inner().iter().enumerate() {
-    //                 for (j, val) in row.iter().enumerate() {
-    //                     result[cols - 1 - j][rows - 1 - i] = val.clone();
-    //                 }
-    //             }
-    //         }
-    //         Rotation::D270 => {
-    //             for (i, row) in self.get_inner().iter().enumerate() {
-    //                 for (j, val) in row.iter().enumerate() {
-    //                     result[cols - 1 - j][i] = val.clone();
-    //                 }
-    //             }
-    //         }
-    //     }
+    /// Returns a copy of the matrix, flipped in the specified direction
+    ///
+    /// `Horizontal` mirrors left to right, `Vertical` mirrors top to bottom
+    #[inline]
+    #[must_use]
+    pub fn flip(&self, direction: Flip) -> Matrix<T> {
+        let size = self.size();
 
-    //     Matrix::from_inner(result)
-    // }
+        self.map_with_pos(|_, pos| {
+            let source = match direction {
+                Flip::Horizontal => UVec2::new(size.x - 1 - pos.x, pos.y),
+                Flip::Vertical => UVec2::new(pos.x, size.y - 1 - pos.y),
+            };
 
-    // /// Returns a copy of the matrix, flipped in the specified direction
-    // #[inline]
-    // #[must_use]
-    // pub fn flip(&self, direction: Flip) -> Matrix<T> {
-    //     match direction {
-    //         Flip::Horizontal => Matrix::from_inner(
-    //             self.get_inner()
-    //                 .iter()
-    //                 .map(|row| row.iter().rev().cloned().collect())
-    //                 .collect(),
-    //         ),
-    //         Flip::Vertical => Matrix::from_inner(self.get_inner().iter().rev().cloned().collect()),
-    //     }
-    // }
+            self.get(source).clone()
+        })
+    }
 }
 
 /// Clockwise rotations in 90 degree increments
-#[derive(Clone, Copy)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum MatrixRotation {
     /// 90 degrees clockwise
     D90,
@@ -189,8 +180,47 @@ pub enum MatrixRotation {
     D270,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Flip {
     Horizontal,
     Vertical,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(size: UVec2, elements: [u32; 6]) -> Matrix<u32> {
+        Matrix::from_elements(elements.to_vec(), size)
+    }
+
+    #[test]
+    fn test_rotate_and_flip() {
+        let wide = UVec2::new(3, 2);
+        let tall = UVec2::new(2, 3);
+
+        // 1 2 3
+        // 4 5 6
+        let m = matrix(wide, [1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(
+            m.rotate(MatrixRotation::D90),
+            matrix(tall, [4, 1, 5, 2, 6, 3])
+        );
+        assert_eq!(
+            m.rotate(MatrixRotation::D180),
+            matrix(wide, [6, 5, 4, 3, 2, 1])
+        );
+        assert_eq!(
+            m.rotate(MatrixRotation::D270),
+            matrix(tall, [3, 6, 2, 5, 1, 4])
+        );
+        assert_eq!(
+            m.rotate(MatrixRotation::D90).rotate(MatrixRotation::D270),
+            m
+        );
+
+        assert_eq!(m.flip(Flip::Horizontal), matrix(wide, [3, 2, 1, 6, 5, 4]));
+        assert_eq!(m.flip(Flip::Vertical), matrix(wide, [4, 5, 6, 1, 2, 3]));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::*;
+
+/// Partitions a grid into the cells closest to each site
+#[derive(Clone, Debug, PartialEq)]
+pub struct Voronoi {
+    pub sites: Vec<UVec2>,
+    /// One weight per site, larger weights claim more cells (power diagram).
+    ///
+    /// With `Euclidian` distances are compared as `distance² - weight`, otherwise as `distance - weight`
+    pub weights: Option<Vec<f32>>,
+    pub metric: DistanceMetric,
+}
+
+impl Voronoi {
+    pub fn new(sites: Vec<UVec2>, metric: DistanceMetric) -> Self {
+        Self {
+            sites,
+            weights: None,
+            metric,
+        }
+    }
+
+    pub fn with_weights(mut self, weights: Vec<f32>) -> Self {
+        assert_eq!(
+            weights.len(),
+            self.sites.len(),
+            "Voronoi needs exactly one weight per site"
+        );
+
+        self.weights = Some(weights);
+        self
+    }
+
+    /// Labels every cell with its closest site
+    ///
+    /// Cells closest to `sites[i]` are labelled `i + 1`, matching `label_regions`
+    /// so the result can be passed to `RegionGraph::from_labels`.
+    /// Ties go to the first site. Every cell is labelled 0 if there are no sites
+    pub fn labels(&self, size: UVec2) -> Matrix<u32> {
+        let elements = size
+            .positions()
+            .into_iter()
+            .map(|pos| self.closest_site(pos).map_or(0, |idx| idx as u32 + 1))
+            .collect();
+
+        Matrix::from_elements(elements, size)
+    }
+
+    /// Index of the closest site to `pos`, taking weights into account
+    pub fn closest_site(&self, pos: UVec2) -> Option<usize> {
+        self.sites
+            .iter()
+            .enumerate()
+            .map(|(idx, &site)| {
+                let dist = self.metric.distance(pos, site);
+
+                let weighted = match (&self.weights, self.metric) {
+                    (None, _) => dist,
+                    (Some(weights), DistanceMetric::Euclidian) => dist * dist - weights[idx],
+                    (Some(weights), _) => dist - weights[idx],
+                };
+
+                (idx, weighted)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Lloyd relaxation, moves each site to the center of its cells `iterations` times
+    ///
+    /// Spreads the sites out, giving more evenly sized regions.
+    /// Sites that don't own any cells stay where they are
+    pub fn relax(&mut self, size: UVec2, iterations: u32) {
+        for _ in 0..iterations {
+            let labels = self.labels(size);
+
+            let mut sums = vec![Vec2::ZERO; self.sites.len()];
+            let mut counts = vec![0_u32; self.sites.len()];
+
+            for (&label, pos) in labels.iter_with_pos() {
+                if label == 0 {
+                    continue;
+                }
+
+                sums[label as usize - 1] += pos.as_vec2();
+                counts[label as usize - 1] += 1;
+            }
+
+            let mut moved = false;
+
+            for (site, (sum, count)) in self.sites.iter_mut().zip(sums.into_iter().zip(counts)) {
+                if count == 0 {
+                    continue;
+                }
+
+                let centroid = (sum / count as f32).round().as_uvec2();
+
+                moved |= centroid != *site;
+                *site = centroid;
+            }
+
+            if !moved {
+                break;
+            }
+        }
+    }
+
+    /// Which sites share a border, nodes are labelled `site index + 1`
+    #[inline]
+    pub fn adjacency(&self, size: UVec2) -> RegionGraph {
+        RegionGraph::from_labels(&self.labels(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voronoi_labels() {
+        let voronoi = Voronoi::new(
+            vec![UVec2::new(1, 1), UVec2::new(8, 1)],
+            DistanceMetric::Euclidian,
+        );
+
+        let labels = voronoi.labels(UVec2::new(10, 3));
+
+        assert_eq!(labels.count_matches(|&l, _| l == 1), 15);
+        assert_eq!(labels.count_matches(|&l, _| l == 2), 15);
+
+        // A weighted site claims the tie and more
+        let weighted = voronoi.with_weights(vec![10.0, 0.0]);
+        let labels = weighted.labels(UVec2::new(10, 3));
+
+        assert!(labels.count_matches(|&l, _| l == 1) > 15);
+    }
+
+    #[test]
+    fn test_voronoi_relax() {
+        let mut voronoi = Voronoi::new(
+            vec![UVec2::new(0, 0), UVec2::new(1, 0)],
+            DistanceMetric::Manhattan,
+        );
+
+        voronoi.relax(UVec2::new(20, 10), 20);
+
+        // Sites spread out towards the centers of each half
+        assert!(voronoi.sites[0].x < 8);
+        assert!(voronoi.sites[1].x > 11);
+        assert_eq!(voronoi.sites[0].y, 5);
+
+        let graph = voronoi.adjacency(UVec2::new(20, 10));
+        assert_eq!(graph.neighbours(1), vec![2]);
+    }
+}
@@ -0,0 +1,342 @@
+use crate::*;
+
+/// A Rect rotated about its center
+///
+/// `rotation` is in radians, positive values turn clockwise with y pointing down
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrientedRect {
+    pub center: Vec2,
+    pub half_extents: Vec2,
+    pub rotation: f32,
+}
+
+impl OrientedRect {
+    #[inline]
+    pub fn new(center: Vec2, half_extents: Vec2, rotation: f32) -> Self {
+        Self {
+            center,
+            half_extents,
+            rotation,
+        }
+    }
+
+    /// The local x and y axes, as unit vectors
+    #[inline]
+    pub fn axes(&self) -> [Vec2; 2] {
+        let x_axis = Vec2::from_angle(self.rotation);
+
+        [x_axis, x_axis.perp()]
+    }
+
+    /// Top left, Top right, Bottom left, Bottom right as they would be before rotating, matching `Rect::corners`
+    pub fn corners(&self) -> [Vec2; 4] {
+        let [x_axis, y_axis] = self.axes();
+        let x = x_axis * self.half_extents.x;
+        let y = y_axis * self.half_extents.y;
+
+        [
+            self.center - x - y,
+            self.center + x - y,
+            self.center - x + y,
+            self.center + x + y,
+        ]
+    }
+
+    /// The corners in clockwise order, for walking the edges
+    #[inline]
+    fn corner_loop(&self) -> [Vec2; 4] {
+        let [top_left, top_right, bottom_left, bottom_right] = self.corners();
+
+        [top_left, top_right, bottom_right, bottom_left]
+    }
+
+    /// The smallest axis-aligned Rect containing every corner
+    pub fn bounding_rect(&self) -> Rect<f32> {
+        let corners = self.corners();
+
+        Rect {
+            top_left: corners.into_iter().reduce(Vec2::min).unwrap(),
+            bottom_right: corners.into_iter().reduce(Vec2::max).unwrap(),
+        }
+    }
+
+    /// Points on the edge count as inside
+    pub fn contains_point(&self, pos: Vec2) -> bool {
+        let [x_axis, y_axis] = self.axes();
+        let offset = pos - self.center;
+
+        offset.dot(x_axis).abs() <= self.half_extents.x
+            && offset.dot(y_axis).abs() <= self.half_extents.y
+    }
+
+    /// The corners as a clockwise polygon, starting from the top left
+    #[inline]
+    pub fn to_polygon(&self) -> Polygon<f32> {
+        Polygon::new(self.corner_loop().to_vec())
+    }
+
+    /// Minimum translation vector, the smallest move of `self` that separates it from `rect`
+    ///
+    /// None if they don't overlap, touching edges don't count
+    #[inline]
+    pub fn overlap_rect(&self, rect: &Rect<f32>) -> Option<Vec2> {
+        self.overlap(&OrientedRect::from(*rect))
+    }
+
+    /// Minimum translation vector, see `overlap_rect`
+    #[inline]
+    pub fn overlap(&self, other: &OrientedRect) -> Option<Vec2> {
+        separating_axis_mtv(&self.corner_loop(), &other.corner_loop())
+    }
+
+    /// Minimum translation vector, see `overlap_rect`
+    ///
+    /// `polygon` must be convex, holes are ignored
+    #[inline]
+    pub fn overlap_polygon(&self, polygon: &Polygon<f32>) -> Option<Vec2> {
+        separating_axis_mtv(&self.corner_loop(), &polygon.exterior)
+    }
+
+    #[inline]
+    pub fn intersects_rect(&self, rect: &Rect<f32>) -> bool {
+        self.overlap_rect(rect).is_some()
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &OrientedRect) -> bool {
+        self.overlap(other).is_some()
+    }
+
+    #[inline]
+    pub fn intersects_polygon(&self, polygon: &Polygon<f32>) -> bool {
+        self.overlap_polygon(polygon).is_some()
+    }
+
+    /// A matrix of `size` with the cells covered by `self` set to `true`, see `Matrix::fill_oriented_rect`
+    pub fn rasterise(&self, size: UVec2) -> Matrix<bool> {
+        let mut matrix = Matrix::splat(size, false);
+        matrix.fill_oriented_rect(self, true);
+        matrix
+    }
+}
+
+/// An unrotated OrientedRect covering the same area
+impl From<Rect<f32>> for OrientedRect {
+    fn from(rect: Rect<f32>) -> Self {
+        let half_extents = (rect.bottom_right - rect.top_left) / 2.0;
+
+        Self::new(rect.top_left + half_extents, half_extents, 0.0)
+    }
+}
+
+/// The tight bounds, see `OrientedRect::bounding_rect`
+impl From<OrientedRect> for Rect<f32> {
+    #[inline]
+    fn from(rect: OrientedRect) -> Self {
+        rect.bounding_rect()
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    /// Sets every cell whose center is inside `rect` to `value`
+    ///
+    /// Each cell covers `pos..pos + 1`, parts of `rect` outside the matrix are skipped
+    pub fn fill_oriented_rect(&mut self, rect: &OrientedRect, value: T) {
+        let bounds = rect.bounding_rect();
+        let size = self.size().as_vec2();
+
+        let min = (bounds.top_left - 0.5).ceil().clamp(Vec2::ZERO, size);
+        let max = (bounds.bottom_right + 0.5).floor().clamp(Vec2::ZERO, size);
+
+        for y in min.y as u32..max.y as u32 {
+            for x in min.x as u32..max.x as u32 {
+                let pos = UVec2::new(x, y);
+
+                if rect.contains_point(pos.as_vec2() + 0.5) {
+                    self.set(pos, value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Separating axis test between two convex loops of points
+///
+/// Returns the shortest vector moving `a` out of `b`, or None if some axis separates them
+fn separating_axis_mtv(a: &[Vec2], b: &[Vec2]) -> Option<Vec2> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<(f32, Vec2)> = None;
+
+    for points in [a, b] {
+        for i in 0..points.len() {
+            let edge = points[(i + 1) % points.len()] - points[i];
+            let Some(axis) = edge.perp().try_normalize() else {
+                continue;
+            };
+
+            let (a_min, a_max) = project(a, axis);
+            let (b_min, b_max) = project(b, axis);
+
+            // How far `a` has to move along `axis`, or against it, to clear `b`
+            // Both are needed as one projection can contain the other
+            let forward = b_max - a_min;
+            let backward = a_max - b_min;
+            let depth = forward.min(backward);
+
+            if depth <= 0.0 {
+                return None;
+            }
+
+            if best.is_none_or(|(best_depth, _)| depth < best_depth) {
+                let axis = if forward < backward { axis } else { -axis };
+
+                best = Some((depth, axis));
+            }
+        }
+    }
+
+    best.map(|(depth, axis)| axis * depth)
+}
+
+fn project(points: &[Vec2], axis: Vec2) -> (f32, f32) {
+    points.iter().map(|point| point.dot(axis)).fold(
+        (f32::INFINITY, f32::NEG_INFINITY),
+        |(min, max), distance| (min.min(distance), max.max(distance)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn test_oriented_rect_corners_and_bounds() {
+        let rect = Rect::<f32>::from_corners(Vec2::new(1.0, 2.0), Vec2::new(5.0, 4.0));
+        let oriented = OrientedRect::from(rect);
+
+        assert_eq!(oriented.center, Vec2::new(3.0, 3.0));
+        assert_eq!(oriented.corners(), rect.corners());
+        assert_eq!(
+            oriented.to_polygon().exterior,
+            vec![
+                Vec2::new(1.0, 2.0),
+                Vec2::new(5.0, 2.0),
+                Vec2::new(5.0, 4.0),
+                Vec2::new(1.0, 4.0),
+            ]
+        );
+        assert_eq!(Rect::from(oriented), rect);
+        assert_eq!(oriented.to_polygon().winding(), Some(Winding::Clockwise));
+
+        let diamond = OrientedRect::new(Vec2::ZERO, Vec2::ONE, FRAC_PI_4);
+        let bounds = diamond.bounding_rect();
+        assert!(
+            (bounds.bottom_right - Vec2::splat(2.0f32.sqrt()))
+                .abs()
+                .max_element()
+                < 1e-5
+        );
+        assert!(
+            (bounds.top_left + Vec2::splat(2.0f32.sqrt()))
+                .abs()
+                .max_element()
+                < 1e-5
+        );
+
+        assert!(diamond.contains_point(Vec2::new(1.3, 0.0)));
+        assert!(!diamond.contains_point(Vec2::new(0.9, 0.9)));
+    }
+
+    #[test]
+    fn test_oriented_rect_separating_axis() {
+        let diamond = OrientedRect::new(Vec2::ZERO, Vec2::ONE, FRAC_PI_4);
+
+        // The diamond's right-most point pokes 0.41 into the Rect
+        let wall = Rect::<f32>::from_corners(Vec2::new(1.0, -3.0), Vec2::new(3.0, 3.0));
+        let mtv = diamond.overlap_rect(&wall).unwrap();
+        assert!((mtv - Vec2::new(1.0 - 2.0f32.sqrt(), 0.0)).length() < 1e-5);
+        assert!(!diamond.intersects_rect(&Rect::from_corners(
+            Vec2::new(1.5, -3.0),
+            Vec2::new(3.0, 3.0)
+        )));
+
+        // Close enough for the bounding Rects to overlap, but separated along the diamond's edge
+        assert!(!diamond.intersects_rect(&Rect::from_corners(
+            Vec2::new(0.8, 0.8),
+            Vec2::new(2.0, 2.0)
+        )));
+
+        let other = OrientedRect::new(Vec2::new(0.0, 2.5), Vec2::ONE, FRAC_PI_4);
+        let mtv = diamond.overlap(&other).unwrap();
+        assert!(mtv.y < 0.0);
+        assert!(
+            diamond
+                .overlap(&OrientedRect {
+                    center: Vec2::new(0.0, 3.0),
+                    ..other
+                })
+                .is_none()
+        );
+
+        // A Rect inside a much larger one has to move past the nearest edge, not the overlap width
+        let big = OrientedRect::new(Vec2::splat(5.0), Vec2::splat(5.0), 0.0);
+        let small = Rect::<f32>::from_corners(Vec2::new(3.0, 4.0), Vec2::new(5.0, 6.0));
+        let mtv = OrientedRect::from(small).overlap(&big).unwrap();
+        assert!((mtv - Vec2::new(-5.0, 0.0)).length() < 1e-5);
+        assert!(!big.intersects_rect(&Rect::from_top_left_and_size(
+            small.top_left + mtv,
+            small.size()
+        )));
+        let mtv = big.overlap_rect(&small).unwrap();
+        assert!((mtv - Vec2::new(5.0, 0.0)).length() < 1e-5);
+        assert!(
+            !OrientedRect {
+                center: big.center + mtv,
+                ..big
+            }
+            .intersects_rect(&small)
+        );
+
+        let triangle = Polygon::<f32>::new(vec![
+            Vec2::new(0.0, -3.0),
+            Vec2::new(0.5, -1.0),
+            Vec2::new(-0.5, -1.0),
+        ]);
+        assert!(diamond.intersects_polygon(&triangle));
+        assert!(!diamond.intersects_polygon(&Polygon::new(
+            triangle.exterior.iter().map(|&pos| pos - Vec2::Y).collect()
+        )));
+    }
+
+    #[test]
+    fn test_oriented_rect_rasterise() {
+        let bar = OrientedRect::new(Vec2::new(3.0, 1.5), Vec2::new(2.0, 0.5), 0.0);
+
+        let matrix = bar.rasterise(UVec2::new(6, 4));
+        let filled: Vec<UVec2> = matrix
+            .positions()
+            .into_iter()
+            .filter(|&pos| *matrix.get(pos))
+            .collect();
+        assert_eq!(filled, (1..5).map(|x| UVec2::new(x, 1)).collect::<Vec<_>>());
+
+        // Turned upright and partly off the bottom edge
+        let upright = OrientedRect {
+            rotation: std::f32::consts::FRAC_PI_2,
+            center: Vec2::new(3.5, 3.0),
+            ..bar
+        };
+        let matrix = upright.rasterise(UVec2::new(6, 4));
+        let filled: Vec<UVec2> = matrix
+            .positions()
+            .into_iter()
+            .filter(|&pos| *matrix.get(pos))
+            .collect();
+        assert_eq!(filled, (1..4).map(|y| UVec2::new(3, y)).collect::<Vec<_>>());
+    }
+}
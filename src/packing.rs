@@ -0,0 +1,559 @@
+use crate::*;
+
+/// How free space is tracked and chosen when packing
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PackingAlgorithm {
+    /// Tracks every maximal free rect, placing items where they leave the shortest side leftover.
+    /// Slowest, but packs the tightest
+    MaxRects,
+    /// Tracks the top edge of the packed items, placing items as low as possible.
+    /// Fast, but wastes the space under overhangs
+    Skyline,
+    /// Splits the chosen free rect in two after each placement, placing items where they leave the least area.
+    /// The result can always be cut apart with straight edge-to-edge cuts
+    Guillotine,
+}
+
+/// Packs rect sizes into one or more bins
+#[derive(Clone, Debug, PartialEq)]
+pub struct RectPacker {
+    pub algorithm: PackingAlgorithm,
+    /// Allows items to be turned 90°, if it fits better
+    pub allow_rotation: bool,
+    /// Minimum gap between items, items can still touch the edges of the bins
+    pub padding: u32,
+    /// Packs the largest items first, which usually packs much tighter.
+    /// Otherwise items are packed in the order given
+    pub sort_by_size: bool,
+}
+
+impl Default for RectPacker {
+    fn default() -> Self {
+        Self {
+            algorithm: PackingAlgorithm::MaxRects,
+            allow_rotation: true,
+            padding: 0,
+            sort_by_size: true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PackedRect {
+    /// Index into the packed sizes
+    pub item: usize,
+    /// Index into the bins
+    pub bin: usize,
+    /// Exclusive, in the same coordinates as the bin
+    pub rect: Rect<u32>,
+    /// The item was turned 90°, so `rect` is `height` wide and `width` tall
+    pub rotated: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackingResult {
+    /// In the order they were placed
+    pub placed: Vec<PackedRect>,
+    /// Indices of the items that didn't fit in any bin
+    pub unfit: Vec<usize>,
+}
+
+impl PackingResult {
+    #[inline]
+    pub fn all_placed(&self) -> bool {
+        self.unfit.is_empty()
+    }
+}
+
+impl RectPacker {
+    pub fn new(algorithm: PackingAlgorithm) -> Self {
+        Self {
+            algorithm,
+            ..Default::default()
+        }
+    }
+
+    /// Places each of `sizes` into the first of `bins` with room for it
+    pub fn pack(&self, sizes: &[UVec2], bins: &[Rect<u32>]) -> PackingResult {
+        // Padding is added to the right & bottom of every item, and bins get the same extra room
+        // so items can still reach their right & bottom edges
+        let padding = UVec2::splat(self.padding);
+
+        let mut states: Vec<Box<dyn PackingBin>> = bins
+            .iter()
+            .map(|bin| {
+                let size = bin.bottom_right.saturating_sub(bin.top_left) + padding;
+
+                match self.algorithm {
+                    PackingAlgorithm::MaxRects => {
+                        Box::new(MaxRectsBin::new(size)) as Box<dyn PackingBin>
+                    }
+                    PackingAlgorithm::Skyline => Box::new(SkylineBin::new(size)),
+                    PackingAlgorithm::Guillotine => Box::new(GuillotineBin::new(size)),
+                }
+            })
+            .collect();
+
+        let mut order = (0..sizes.len()).collect_vec();
+
+        if self.sort_by_size {
+            order.sort_by_key(|&idx| {
+                let size = sizes[idx];
+
+                core::cmp::Reverse((size.max_element(), size.as_u64vec2().element_product()))
+            });
+        }
+
+        let mut result = PackingResult::default();
+
+        'items: for item in order {
+            let size = sizes[item];
+
+            for (bin, state) in states.iter_mut().enumerate() {
+                let mut best: Option<(UVec2, (u64, u32), bool)> = None;
+
+                for rotated in [false, true] {
+                    if rotated && (!self.allow_rotation || size.x == size.y) {
+                        continue;
+                    }
+
+                    let oriented = if rotated {
+                        UVec2::new(size.y, size.x)
+                    } else {
+                        size
+                    };
+
+                    if let Some((top_left, score)) = state.find(oriented + padding)
+                        && best.is_none_or(|(_, best_score, _)| score < best_score)
+                    {
+                        best = Some((top_left, score, rotated));
+                    }
+                }
+
+                let Some((top_left, _, rotated)) = best else {
+                    continue;
+                };
+
+                let oriented = if rotated {
+                    UVec2::new(size.y, size.x)
+                } else {
+                    size
+                };
+
+                state.place(Rect::from_top_left_and_size(top_left, oriented + padding));
+
+                result.placed.push(PackedRect {
+                    item,
+                    bin,
+                    rect: Rect::from_top_left_and_size(bins[bin].top_left + top_left, oriented),
+                    rotated,
+                });
+
+                continue 'items;
+            }
+
+            result.unfit.push(item);
+        }
+
+        result.unfit.sort_unstable();
+
+        result
+    }
+}
+
+/// Free space of a single bin, in coordinates local to the bin
+trait PackingBin {
+    /// Best top left for a rect of `size`, and its score (lower is better)
+    fn find(&self, size: UVec2) -> Option<(UVec2, (u64, u32))>;
+
+    /// Marks a rect returned by `find` as used
+    fn place(&mut self, rect: Rect<u32>);
+}
+
+struct MaxRectsBin {
+    /// Maximal free rects, these can overlap each other
+    free: Vec<Rect<u32>>,
+}
+
+impl MaxRectsBin {
+    fn new(size: UVec2) -> Self {
+        Self {
+            free: vec![Rect::from_top_left_and_size(UVec2::ZERO, size)],
+        }
+    }
+}
+
+impl PackingBin for MaxRectsBin {
+    /// Best short side fit
+    fn find(&self, size: UVec2) -> Option<(UVec2, (u64, u32))> {
+        self.free
+            .iter()
+            .filter(|free| free.width() >= size.x && free.height() >= size.y)
+            .map(|free| {
+                let leftover = free.size() - size;
+
+                (
+                    free.top_left,
+                    (leftover.min_element().into(), leftover.max_element()),
+                )
+            })
+            .min_by_key(|&(_, score)| score)
+    }
+
+    fn place(&mut self, rect: Rect<u32>) {
+        let mut split = Vec::with_capacity(self.free.len() + 4);
+
+        for free in self.free.drain(..) {
+            if !free.overlaps(&rect) {
+                split.push(free);
+                continue;
+            }
+
+            // The maximal rects left on each side of `rect`, these overlap at the corners
+            if rect.top_left.x > free.top_left.x {
+                split.push(Rect::from_corners(
+                    free.top_left,
+                    UVec2::new(rect.top_left.x, free.bottom_right.y),
+                ));
+            }
+            if rect.bottom_right.x < free.bottom_right.x {
+                split.push(Rect::from_corners(
+                    UVec2::new(rect.bottom_right.x, free.top_left.y),
+                    free.bottom_right,
+                ));
+            }
+            if rect.top_left.y > free.top_left.y {
+                split.push(Rect::from_corners(
+                    free.top_left,
+                    UVec2::new(free.bottom_right.x, rect.top_left.y),
+                ));
+            }
+            if rect.bottom_right.y < free.bottom_right.y {
+                split.push(Rect::from_corners(
+                    UVec2::new(free.top_left.x, rect.bottom_right.y),
+                    free.bottom_right,
+                ));
+            }
+        }
+
+        // Free rects inside another free rect are redundant, for duplicates keep the first
+        self.free = split
+            .iter()
+            .enumerate()
+            .filter(|&(idx, rect)| {
+                !split.iter().enumerate().any(|(other_idx, other)| {
+                    other_idx != idx
+                        && other.contains_rect(rect)
+                        && (other != rect || other_idx < idx)
+                })
+            })
+            .map(|(_, &rect)| rect)
+            .collect();
+    }
+}
+
+/// A horizontal segment of the skyline
+#[derive(Copy, Clone, Debug)]
+struct SkylineSegment {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+struct SkylineBin {
+    size: UVec2,
+    /// Left-to-right, covering the full width
+    segments: Vec<SkylineSegment>,
+}
+
+impl SkylineBin {
+    fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            segments: vec![SkylineSegment {
+                x: 0,
+                y: 0,
+                width: size.x,
+            }],
+        }
+    }
+
+    /// The lowest y a rect of `size` can sit at, starting at the left of segment `idx`
+    fn fit(&self, idx: usize, size: UVec2) -> Option<u32> {
+        if self.segments[idx].x + size.x > self.size.x {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut remaining = size.x;
+
+        for segment in &self.segments[idx..] {
+            y = y.max(segment.y);
+
+            if y + size.y > self.size.y {
+                return None;
+            }
+
+            remaining = remaining.saturating_sub(segment.width);
+
+            if remaining == 0 {
+                break;
+            }
+        }
+
+        Some(y)
+    }
+}
+
+impl PackingBin for SkylineBin {
+    /// Bottom left, the lowest top edge then the furthest left
+    fn find(&self, size: UVec2) -> Option<(UVec2, (u64, u32))> {
+        (0..self.segments.len())
+            .filter_map(|idx| {
+                let x = self.segments[idx].x;
+
+                self.fit(idx, size)
+                    .map(|y| (UVec2::new(x, y), ((y + size.y).into(), x)))
+            })
+            .min_by_key(|&(_, score)| score)
+    }
+
+    fn place(&mut self, rect: Rect<u32>) {
+        let Some(idx) = self
+            .segments
+            .iter()
+            .position(|segment| segment.x == rect.top_left.x)
+        else {
+            return;
+        };
+
+        self.segments.insert(
+            idx,
+            SkylineSegment {
+                x: rect.top_left.x,
+                y: rect.bottom_right.y,
+                width: rect.width(),
+            },
+        );
+
+        // Trim the segments now covered by the new one
+        let end = rect.bottom_right.x;
+
+        while let Some(segment) = self.segments.get_mut(idx + 1) {
+            if segment.x >= end {
+                break;
+            }
+
+            let covered = end - segment.x;
+
+            if segment.width <= covered {
+                self.segments.remove(idx + 1);
+            } else {
+                segment.x += covered;
+                segment.width -= covered;
+                break;
+            }
+        }
+
+        // Merge neighbours at the same height
+        self.segments.dedup_by(|next, prev| {
+            if next.y == prev.y {
+                prev.width += next.width;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+struct GuillotineBin {
+    /// Disjoint free rects
+    free: Vec<Rect<u32>>,
+}
+
+impl GuillotineBin {
+    fn new(size: UVec2) -> Self {
+        Self {
+            free: vec![Rect::from_top_left_and_size(UVec2::ZERO, size)],
+        }
+    }
+}
+
+impl PackingBin for GuillotineBin {
+    /// Best area fit
+    fn find(&self, size: UVec2) -> Option<(UVec2, (u64, u32))> {
+        self.free
+            .iter()
+            .filter(|free| free.width() >= size.x && free.height() >= size.y)
+            .map(|free| {
+                let leftover = free.size() - size;
+
+                (
+                    free.top_left,
+                    (
+                        free.size().as_u64vec2().element_product()
+                            - size.as_u64vec2().element_product(),
+                        leftover.min_element(),
+                    ),
+                )
+            })
+            .min_by_key(|&(_, score)| score)
+    }
+
+    fn place(&mut self, rect: Rect<u32>) {
+        let Some(idx) = self
+            .free
+            .iter()
+            .position(|free| free.top_left == rect.top_left && free.contains_rect(&rect))
+        else {
+            return;
+        };
+
+        let free = self.free.swap_remove(idx);
+        let leftover = free.size() - rect.size();
+
+        // Split along the shorter leftover axis, so the larger leftover piece stays as big as possible
+        let (right, bottom) = if leftover.x < leftover.y {
+            (
+                Rect::from_corners(
+                    UVec2::new(rect.bottom_right.x, free.top_left.y),
+                    UVec2::new(free.bottom_right.x, rect.bottom_right.y),
+                ),
+                Rect::from_corners(
+                    UVec2::new(free.top_left.x, rect.bottom_right.y),
+                    free.bottom_right,
+                ),
+            )
+        } else {
+            (
+                Rect::from_corners(
+                    UVec2::new(rect.bottom_right.x, free.top_left.y),
+                    free.bottom_right,
+                ),
+                Rect::from_corners(
+                    UVec2::new(free.top_left.x, rect.bottom_right.y),
+                    UVec2::new(rect.bottom_right.x, free.bottom_right.y),
+                ),
+            )
+        };
+
+        self.free.extend(
+            [right, bottom]
+                .into_iter()
+                .filter(|piece| !piece.is_empty()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizes() -> Vec<UVec2> {
+        vec![
+            UVec2::new(20, 10),
+            UVec2::new(10, 30),
+            UVec2::new(16, 16),
+            UVec2::new(8, 8),
+            UVec2::new(30, 6),
+            UVec2::new(12, 20),
+            UVec2::new(6, 6),
+            UVec2::new(14, 9),
+        ]
+    }
+
+    fn assert_valid(result: &PackingResult, sizes: &[UVec2], bins: &[Rect<u32>], padding: u32) {
+        for packed in &result.placed {
+            let size = sizes[packed.item];
+            let expected = if packed.rotated {
+                UVec2::new(size.y, size.x)
+            } else {
+                size
+            };
+
+            assert_eq!(packed.rect.size(), expected);
+            assert!(bins[packed.bin].contains_rect(&packed.rect));
+        }
+
+        for (a, b) in result.placed.iter().tuple_combinations() {
+            if a.bin == b.bin {
+                // Items are padded on the right & bottom
+                let [a, b] =
+                    [a.rect, b.rect].map(|rect| rect.outset(&Margins::new(0, padding, 0, padding)));
+
+                assert!(!a.overlaps(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rect_packer() {
+        let sizes = sizes();
+        let bins = [Rect::from_corners(UVec2::ZERO, UVec2::new(48, 48))];
+
+        for algorithm in [
+            PackingAlgorithm::MaxRects,
+            PackingAlgorithm::Skyline,
+            PackingAlgorithm::Guillotine,
+        ] {
+            let packer = RectPacker::new(algorithm);
+            let result = packer.pack(&sizes, &bins);
+
+            assert!(result.all_placed(), "{algorithm:?} {:?}", result.unfit);
+            assert_valid(&result, &sizes, &bins, 0);
+        }
+    }
+
+    #[test]
+    fn test_rect_packer_large_items() {
+        // Areas past u32::MAX
+        let sizes = [UVec2::new(100_000, 50_000), UVec2::new(70_000, 70_000)];
+        let bins = [Rect::from_corners(
+            UVec2::ZERO,
+            UVec2::new(200_000, 200_000),
+        )];
+
+        for algorithm in [
+            PackingAlgorithm::MaxRects,
+            PackingAlgorithm::Skyline,
+            PackingAlgorithm::Guillotine,
+        ] {
+            let result = RectPacker::new(algorithm).pack(&sizes, &bins);
+
+            assert!(result.all_placed(), "{algorithm:?} {:?}", result.unfit);
+            assert_valid(&result, &sizes, &bins, 0);
+        }
+    }
+
+    #[test]
+    fn test_rect_packer_bins_and_padding() {
+        let sizes = sizes();
+        let bins = [
+            Rect::from_corners(UVec2::new(100, 100), UVec2::new(132, 132)),
+            Rect::from_corners(UVec2::ZERO, UVec2::new(24, 24)),
+        ];
+
+        let packer = RectPacker {
+            allow_rotation: false,
+            padding: 2,
+            ..Default::default()
+        };
+        let result = packer.pack(&sizes, &bins);
+
+        assert_valid(&result, &sizes, &bins, 2);
+        assert!(result.placed.iter().all(|packed| !packed.rotated));
+        assert!(result.placed.iter().any(|packed| packed.bin == 1));
+
+        // Too tall for either bin without rotation
+        let result = packer.pack(&[UVec2::new(8, 40)], &bins);
+        assert_eq!(result.unfit, vec![0]);
+
+        let rotating = RectPacker::new(PackingAlgorithm::Skyline);
+        let result = rotating.pack(
+            &[UVec2::new(8, 40)],
+            &[Rect::from_corners(UVec2::ZERO, UVec2::new(40, 8))],
+        );
+        assert!(result.placed[0].rotated);
+    }
+}
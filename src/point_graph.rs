@@ -0,0 +1,437 @@
+use crate::*;
+
+/// Delaunay triangulation of a point set
+///
+/// No point lies inside the circumcircle of any triangle,
+/// which avoids long thin triangles and makes the edges a good base for connecting nearby points
+#[derive(Clone, Debug, PartialEq)]
+pub struct Delaunay {
+    pub points: Vec<Vec2>,
+    /// Indices into `points`, wound clockwise (with y pointing down)
+    pub triangles: Vec<[u32; 3]>,
+    /// Edges to points that aren't in any triangle, included by `edges`
+    ///
+    /// Links each duplicate point to its first occurrence, and keeps the hull edges of thin point sets
+    /// whose only triangles also used a corner of the temporary super triangle
+    pub extra_edges: Vec<(u32, u32)>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Circumcircle {
+    center: DVec2,
+    radius_squared: f64,
+}
+
+impl Circumcircle {
+    fn new(a: DVec2, b: DVec2, c: DVec2) -> Self {
+        let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+
+        let center = DVec2::new(
+            (a.length_squared() * (b.y - c.y)
+                + b.length_squared() * (c.y - a.y)
+                + c.length_squared() * (a.y - b.y))
+                / d,
+            (a.length_squared() * (c.x - b.x)
+                + b.length_squared() * (a.x - c.x)
+                + c.length_squared() * (b.x - a.x))
+                / d,
+        );
+
+        Self {
+            center,
+            radius_squared: center.distance_squared(a),
+        }
+    }
+
+    #[inline]
+    fn contains(&self, point: DVec2) -> bool {
+        self.center.distance_squared(point) < self.radius_squared
+    }
+}
+
+impl Delaunay {
+    /// Bowyer-Watson triangulation, O(n²)
+    ///
+    /// Duplicate points aren't triangulated, see `extra_edges`. If every point is collinear there are no triangles
+    pub fn new(points: Vec<Vec2>) -> Self {
+        let mut triangulation = Self {
+            points,
+            triangles: Vec::new(),
+            extra_edges: Vec::new(),
+        };
+
+        if triangulation.points.len() < 3 {
+            return triangulation;
+        }
+
+        let n = triangulation.points.len();
+        let mut vertices: Vec<DVec2> = triangulation.points.iter().map(|p| p.as_dvec2()).collect();
+
+        // Super triangle containing every point, removed at the end
+        let min = vertices.iter().fold(DVec2::MAX, |acc, &p| acc.min(p));
+        let max = vertices.iter().fold(DVec2::MIN, |acc, &p| acc.max(p));
+        let center = (min + max) / 2.0;
+        let extent = (max - min).max_element().max(1.0) * 20.0;
+
+        vertices.push(center + DVec2::new(-extent, -extent));
+        vertices.push(center + DVec2::new(extent, -extent));
+        vertices.push(center + DVec2::new(0.0, extent));
+
+        let super_idx = n as u32;
+        let new_triangle = |[a, b, c]: [u32; 3], vertices: &[DVec2]| {
+            let circle = Circumcircle::new(
+                vertices[a as usize],
+                vertices[b as usize],
+                vertices[c as usize],
+            );
+
+            ([a, b, c], circle)
+        };
+
+        let mut triangles = vec![new_triangle(
+            [super_idx, super_idx + 1, super_idx + 2],
+            &vertices,
+        )];
+        let mut inserted: HashMap<(u64, u64), u32> = HashMap::new();
+
+        for idx in 0..n as u32 {
+            let point = vertices[idx as usize];
+
+            // Adding 0.0 turns -0.0 into 0.0, so both have the same bits
+            let key = ((point.x + 0.0).to_bits(), (point.y + 0.0).to_bits());
+
+            if let Some(&first) = inserted.get(&key) {
+                triangulation.extra_edges.push((first, idx));
+                continue;
+            }
+            inserted.insert(key, idx);
+
+            // Edges of the hole left by removing every triangle whose circumcircle contains the point
+            let mut boundary: Vec<(u32, u32)> = Vec::new();
+
+            triangles.retain(|&([a, b, c], circle)| {
+                if !circle.contains(point) {
+                    return true;
+                }
+
+                for edge in [(a, b), (b, c), (c, a)] {
+                    // Edges shared by two removed triangles are inside the hole
+                    match boundary.iter().position(|&(x, y)| (y, x) == edge) {
+                        Some(shared) => {
+                            boundary.swap_remove(shared);
+                        }
+                        None => boundary.push(edge),
+                    }
+                }
+
+                false
+            });
+
+            for (a, b) in boundary {
+                triangles.push(new_triangle([a, b, idx], &vertices));
+            }
+        }
+
+        let (triangles, super_triangles): (Vec<_>, Vec<_>) = triangles
+            .into_iter()
+            .map(|(triangle, _)| triangle)
+            .partition(|triangle| triangle.iter().all(|&v| v < super_idx));
+
+        triangulation.triangles = triangles
+            .into_iter()
+            .map(|[a, b, c]| {
+                // Make the winding consistent
+                let (pa, pb, pc) = (
+                    vertices[a as usize],
+                    vertices[b as usize],
+                    vertices[c as usize],
+                );
+
+                if (pb - pa).perp_dot(pc - pa) < 0.0 {
+                    [a, c, b]
+                } else {
+                    [a, b, c]
+                }
+            })
+            .collect();
+
+        // A finite super triangle can take the place of hull edges of thin point sets,
+        // the edges between real points of its triangles keep those points connected
+        if !triangulation.triangles.is_empty() {
+            let triangle_edges: HashSet<(u32, u32)> = triangulation
+                .triangles
+                .iter()
+                .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+                .map(|(a, b)| (a.min(b), a.max(b)))
+                .collect();
+
+            triangulation.extra_edges.extend(
+                super_triangles
+                    .into_iter()
+                    .flat_map(|[a, b, c]| [(a, b), (b, c), (c, a)])
+                    .filter(|&(a, b)| a < super_idx && b < super_idx)
+                    .map(|(a, b)| (a.min(b), a.max(b)))
+                    .filter(|edge| !triangle_edges.contains(edge)),
+            );
+        }
+
+        triangulation
+    }
+
+    /// Unique triangle edges and `extra_edges` as `(a, b)` with `a < b`, sorted
+    ///
+    /// If there are no triangles (every point is collinear) the points are chained in order along the line instead
+    pub fn edges(&self) -> Vec<(u32, u32)> {
+        if self.triangles.is_empty() {
+            return (0..self.points.len() as u32)
+                .sorted_by(|&a, &b| {
+                    let (pa, pb) = (self.points[a as usize], self.points[b as usize]);
+
+                    pa.x.total_cmp(&pb.x).then(pa.y.total_cmp(&pb.y))
+                })
+                .tuple_windows()
+                .map(|(a, b)| (a.min(b), a.max(b)))
+                .sorted()
+                .dedup()
+                .collect();
+        }
+
+        self.triangles
+            .iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .chain(self.extra_edges.iter().copied())
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
+    /// Edges whose diametral circle (the circle with the edge as its diameter) contains no other point
+    pub fn gabriel_edges(&self) -> Vec<(u32, u32)> {
+        self.edges()
+            .into_iter()
+            .filter(|&(a, b)| {
+                let (pa, pb) = (self.points[a as usize], self.points[b as usize]);
+                let center = (pa + pb) / 2.0;
+                let radius_squared = pa.distance_squared(pb) / 4.0;
+
+                self.points.iter().enumerate().all(|(idx, p)| {
+                    idx as u32 == a
+                        || idx as u32 == b
+                        || p.distance_squared(center) >= radius_squared
+                })
+            })
+            .collect()
+    }
+
+    /// Relative neighbourhood graph, edges with no other point closer to both ends than they are to each other
+    ///
+    /// A subset of the Gabriel graph, and a superset of the minimum spanning tree
+    pub fn relative_neighbourhood_edges(&self) -> Vec<(u32, u32)> {
+        self.edges()
+            .into_iter()
+            .filter(|&(a, b)| {
+                let (pa, pb) = (self.points[a as usize], self.points[b as usize]);
+                let length_squared = pa.distance_squared(pb);
+
+                self.points.iter().enumerate().all(|(idx, p)| {
+                    idx as u32 == a
+                        || idx as u32 == b
+                        || p.distance_squared(pa).max(p.distance_squared(pb)) >= length_squared
+                })
+            })
+            .collect()
+    }
+
+    /// Minimum spanning tree of the triangulation, which is also the euclidean MST of the points
+    #[inline]
+    pub fn minimum_spanning_tree(&self) -> Vec<(u32, u32)> {
+        minimum_spanning_tree(&self.points, &self.edges())
+    }
+}
+
+/// Kruskal's algorithm, the subset of `edges` connecting every point with the shortest total length
+///
+/// Returns a forest if `edges` don't connect every point
+pub fn minimum_spanning_tree(points: &[Vec2], edges: &[(u32, u32)]) -> Vec<(u32, u32)> {
+    let mut sets = UnionFind::new(points.len());
+
+    edges
+        .iter()
+        .copied()
+        .sorted_by(|&(a1, b1), &(a2, b2)| {
+            let length_1 = points[a1 as usize].distance_squared(points[b1 as usize]);
+            let length_2 = points[a2 as usize].distance_squared(points[b2 as usize]);
+
+            length_1.total_cmp(&length_2).then((a1, b1).cmp(&(a2, b2)))
+        })
+        .filter(|&(a, b)| sets.union(a, b))
+        .collect()
+}
+
+/// Disjoint sets of indices, with path compression and union by size
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnionFind {
+    parents: Vec<u32>,
+    sizes: Vec<u32>,
+}
+
+impl UnionFind {
+    /// `len` sets, each containing only their own index
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len as u32).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    /// Representative index of the set containing `idx`
+    pub fn find(&mut self, idx: u32) -> u32 {
+        let mut root = idx;
+
+        while self.parents[root as usize] != root {
+            root = self.parents[root as usize];
+        }
+
+        // Point everything on the path directly at the root
+        let mut current = idx;
+
+        while current != root {
+            let next = self.parents[current as usize];
+            self.parents[current as usize] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`
+    ///
+    /// Returns false if they were already in the same set
+    pub fn union(&mut self, a: u32, b: u32) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+
+        if a == b {
+            return false;
+        }
+
+        if self.sizes[a as usize] < self.sizes[b as usize] {
+            core::mem::swap(&mut a, &mut b);
+        }
+
+        self.parents[b as usize] = a;
+        self.sizes[a as usize] += self.sizes[b as usize];
+
+        true
+    }
+
+    #[inline]
+    pub fn is_connected(&mut self, a: u32, b: u32) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_points() -> Vec<Vec2> {
+        vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(0.0, 10.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(5.0, 4.0),
+        ]
+    }
+
+    #[test]
+    fn test_delaunay() {
+        let delaunay = Delaunay::new(grid_points());
+
+        assert_eq!(delaunay.triangles.len(), 4);
+        // Every corner connects to the middle point, plus the 4 sides
+        assert_eq!(delaunay.edges().len(), 8);
+
+        for &[a, b, c] in &delaunay.triangles {
+            let circle = Circumcircle::new(
+                delaunay.points[a as usize].as_dvec2(),
+                delaunay.points[b as usize].as_dvec2(),
+                delaunay.points[c as usize].as_dvec2(),
+            );
+
+            for (idx, p) in delaunay.points.iter().enumerate() {
+                if ![a, b, c].contains(&(idx as u32)) {
+                    assert!(!circle.contains(p.as_dvec2()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_delaunay_collinear() {
+        let delaunay = Delaunay::new(vec![
+            Vec2::new(2.0, 0.0),
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+        ]);
+
+        assert!(delaunay.triangles.is_empty());
+        assert_eq!(delaunay.edges(), vec![(0, 2), (1, 2)]);
+    }
+
+    #[test]
+    fn test_delaunay_duplicates_and_thin_sets() {
+        let mut points = grid_points();
+        points.push(points[4]);
+        points.push(points[0]);
+
+        let delaunay = Delaunay::new(points);
+        assert_eq!(delaunay.extra_edges, vec![(4, 5), (0, 6)]);
+        assert_eq!(delaunay.minimum_spanning_tree().len(), 6);
+
+        // Nearly collinear, so some points are only in triangles with a super triangle corner
+        let thin = (0..20)
+            .map(|i| Vec2::new(i as f32 * 10.0, ((i * 7) % 5) as f32 * 0.01))
+            .collect_vec();
+
+        let delaunay = Delaunay::new(thin.clone());
+        assert!(!delaunay.triangles.is_empty());
+        assert!(!delaunay.extra_edges.is_empty());
+        assert_eq!(delaunay.minimum_spanning_tree().len(), thin.len() - 1);
+    }
+
+    #[test]
+    fn test_geometric_graphs() {
+        let delaunay = Delaunay::new(grid_points());
+
+        let mst = delaunay.minimum_spanning_tree();
+        let rng = delaunay.relative_neighbourhood_edges();
+        let gabriel = delaunay.gabriel_edges();
+
+        assert_eq!(mst.len(), 4);
+        // Every corner is closest to the middle point
+        assert!(mst.iter().all(|&(_, b)| b == 4));
+
+        // MST ⊆ RNG ⊆ Gabriel ⊆ Delaunay
+        assert!(mst.iter().all(|edge| rng.contains(edge)));
+        assert!(rng.iter().all(|edge| gabriel.contains(edge)));
+        assert!(gabriel.len() <= delaunay.edges().len());
+
+        // The middle point is inside the diametral circle of the top side
+        assert!(!gabriel.contains(&(0, 1)));
+    }
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(4);
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(!sets.union(1, 0));
+        assert!(!sets.is_connected(0, 3));
+
+        sets.union(1, 2);
+        assert!(sets.is_connected(0, 3));
+    }
+}
@@ -0,0 +1,374 @@
+use crate::*;
+
+/// A simple polygon with optional holes
+///
+/// The exterior and each hole are closed loops, the last point connects back to the first (it is not repeated)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon<T: RectScalar> {
+    pub exterior: Vec<T::V2>,
+    pub holes: Vec<Vec<T::V2>>,
+}
+
+impl<T: RectScalar> Polygon<T> {
+    pub fn new(exterior: Vec<T::V2>) -> Self {
+        Self {
+            exterior,
+            holes: Vec::new(),
+        }
+    }
+
+    pub fn with_holes(exterior: Vec<T::V2>, holes: Vec<Vec<T::V2>>) -> Self {
+        Self { exterior, holes }
+    }
+
+    #[inline]
+    pub fn has_holes(&self) -> bool {
+        !self.holes.is_empty()
+    }
+
+    /// The smallest convex polygon containing every point, using Andrew's monotone chain
+    ///
+    /// The hull winds clockwise (with y pointing down) and skips collinear points.
+    /// Fewer than 3 distinct points give a hull with fewer than 3 points
+    pub fn convex_hull(points: &[T::V2]) -> Self {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|&a, &b| {
+            let (a, b) = (T::v2_to_dvec2(a), T::v2_to_dvec2(b));
+            a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+        });
+        sorted.dedup();
+
+        if sorted.len() < 3 {
+            return Self::new(sorted);
+        }
+
+        let turn = |o: T::V2, a: T::V2, b: T::V2| {
+            let o = T::v2_to_dvec2(o);
+            (T::v2_to_dvec2(a) - o).perp_dot(T::v2_to_dvec2(b) - o)
+        };
+
+        let mut hull: Vec<T::V2> = Vec::with_capacity(sorted.len() + 1);
+
+        // Lower hull left to right, then upper hull right to left
+        for &pos in &sorted {
+            while hull.len() >= 2 && turn(hull[hull.len() - 2], hull[hull.len() - 1], pos) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(pos);
+        }
+
+        let lower_len = hull.len() + 1;
+
+        for &pos in sorted.iter().rev().skip(1) {
+            while hull.len() >= lower_len
+                && turn(hull[hull.len() - 2], hull[hull.len() - 1], pos) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(pos);
+        }
+
+        // The upper hull ends back at the first point
+        hull.pop();
+
+        Self::new(hull)
+    }
+
+    /// Area of the exterior, positive when it winds clockwise with y pointing down
+    ///
+    /// Holes are ignored. Computed in f64, so integer scalars can't overflow
+    #[inline]
+    pub fn signed_area(&self) -> f64 {
+        loop_signed_area::<T>(&self.exterior)
+    }
+
+    /// Area of the exterior minus the area of the holes
+    pub fn area(&self) -> f64 {
+        self.signed_area().abs()
+            - self
+                .holes
+                .iter()
+                .map(|hole| loop_signed_area::<T>(hole).abs())
+                .sum::<f64>()
+    }
+
+    /// None if the exterior has no area
+    pub fn winding(&self) -> Option<Winding> {
+        let area = self.signed_area();
+
+        if area > 0.0 {
+            Some(Winding::Clockwise)
+        } else if area < 0.0 {
+            Some(Winding::AntiClockwise)
+        } else {
+            None
+        }
+    }
+
+    /// Centre of mass, taking holes into account. None if the polygon has no area
+    pub fn centroid(&self) -> Option<DVec2> {
+        let (exterior_area, exterior_centroid) = loop_area_and_centroid::<T>(&self.exterior);
+
+        let mut area = exterior_area;
+        let mut moment = exterior_centroid * exterior_area;
+
+        for hole in &self.holes {
+            let (hole_area, hole_centroid) = loop_area_and_centroid::<T>(hole);
+
+            area -= hole_area;
+            moment -= hole_centroid * hole_area;
+        }
+
+        (area > 0.0).then(|| moment / area)
+    }
+
+    /// Whether `pos` is inside the exterior and outside every hole, using the non-zero winding rule
+    ///
+    /// Points exactly on an edge may go either way
+    pub fn contains_point(&self, pos: T::V2) -> bool {
+        let pos = T::v2_to_dvec2(pos);
+
+        loop_winding_number::<T>(&self.exterior, pos) != 0
+            && self
+                .holes
+                .iter()
+                .all(|hole| loop_winding_number::<T>(hole, pos) == 0)
+    }
+
+    /// Every edge of the exterior, then of each hole
+    pub fn edges(&self) -> impl Iterator<Item = Segment<T>> + '_ {
+        std::iter::once(&self.exterior)
+            .chain(&self.holes)
+            .flat_map(|points| loop_edges::<T>(points))
+    }
+
+    /// Touching counts as intersecting
+    pub fn intersects_segment(&self, segment: &Segment<T>) -> bool {
+        self.contains_point(segment.start)
+            || self.edges().any(|edge| edge.intersects_segment(segment))
+    }
+
+    /// Touching counts as intersecting, see `covered_region` for what an integer Rect covers
+    pub fn intersects_rect(&self, rect: &Rect<T>) -> bool {
+        covered_region(rect).is_some()
+            && (self.contains_point(rect.top_left)
+                || self.edges().any(|edge| edge.intersects_rect(rect)))
+    }
+
+    /// Touching counts as intersecting
+    pub fn intersects_circle(&self, circle: &Circle<T>) -> bool {
+        self.contains_point(circle.center)
+            || self.edges().any(|edge| circle.intersects_segment(&edge))
+    }
+
+    /// None if the exterior has no points
+    ///
+    /// For integer scalars `positions()` include every vertex, saturating at the scalar's limits
+    pub fn bounding_rect(&self) -> Option<Rect<T>> {
+        let bounds = Rect::<T>::bounds_of(self.exterior.iter().copied()).ok()?;
+
+        Some(Rect {
+            top_left: bounds.top_left,
+            bottom_right: T::v2_new(
+                one_past(T::v2_x(bounds.bottom_right)),
+                one_past(T::v2_y(bounds.bottom_right)),
+            ),
+        })
+    }
+}
+
+/// Direction the exterior of a polygon is wound in, with y pointing down
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Winding {
+    Clockwise,
+    AntiClockwise,
+}
+
+fn loop_edges<T: RectScalar>(points: &[T::V2]) -> impl Iterator<Item = Segment<T>> + '_ {
+    (0..points.len()).map(move |i| Segment::new(points[i], points[(i + 1) % points.len()]))
+}
+
+fn loop_signed_area<T: RectScalar>(points: &[T::V2]) -> f64 {
+    loop_edges::<T>(points)
+        .map(|edge| T::v2_to_dvec2(edge.start).perp_dot(T::v2_to_dvec2(edge.end)))
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Unsigned area and centroid of a single loop
+fn loop_area_and_centroid<T: RectScalar>(points: &[T::V2]) -> (f64, DVec2) {
+    let mut twice_area = 0.0;
+    let mut moment = DVec2::ZERO;
+
+    for edge in loop_edges::<T>(points) {
+        let (a, b) = (T::v2_to_dvec2(edge.start), T::v2_to_dvec2(edge.end));
+        let cross = a.perp_dot(b);
+
+        twice_area += cross;
+        moment += (a + b) * cross;
+    }
+
+    if twice_area == 0.0 {
+        return (0.0, DVec2::ZERO);
+    }
+
+    ((twice_area / 2.0).abs(), moment / (3.0 * twice_area))
+}
+
+fn loop_winding_number<T: RectScalar>(points: &[T::V2], pos: DVec2) -> i32 {
+    let mut winding = 0;
+
+    for edge in loop_edges::<T>(points) {
+        let (a, b) = (T::v2_to_dvec2(edge.start), T::v2_to_dvec2(edge.end));
+        let side = (b - a).perp_dot(pos - a);
+
+        if a.y <= pos.y {
+            if b.y > pos.y && side > 0.0 {
+                winding += 1;
+            }
+        } else if b.y <= pos.y && side < 0.0 {
+            winding -= 1;
+        }
+    }
+
+    winding
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polygon_measurements() {
+        // Clockwise with y pointing down
+        let square = Polygon::<i32>::with_holes(
+            vec![
+                IVec2::new(0, 0),
+                IVec2::new(4, 0),
+                IVec2::new(4, 4),
+                IVec2::new(0, 4),
+            ],
+            vec![vec![
+                IVec2::new(2, 0),
+                IVec2::new(4, 0),
+                IVec2::new(4, 4),
+                IVec2::new(2, 4),
+            ]],
+        );
+
+        assert_eq!(square.signed_area(), 16.0);
+        assert_eq!(square.area(), 8.0);
+        assert_eq!(square.winding(), Some(Winding::Clockwise));
+        assert_eq!(square.centroid(), Some(DVec2::new(1.0, 2.0)));
+        assert_eq!(
+            square.bounding_rect(),
+            Some(Rect::from_corners(IVec2::ZERO, IVec2::splat(5)))
+        );
+        assert!(
+            square
+                .exterior
+                .iter()
+                .all(|&pos| square.bounding_rect().unwrap().contains_pos(pos))
+        );
+        assert_eq!(square.edges().count(), 8);
+
+        let mut reversed = square.exterior.clone();
+        reversed.reverse();
+        assert_eq!(
+            Polygon::<i32>::new(reversed).winding(),
+            Some(Winding::AntiClockwise)
+        );
+        assert_eq!(
+            Polygon::<i32>::new(vec![IVec2::ZERO, IVec2::ONE, IVec2::splat(2)]).centroid(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_polygon_contains_and_intersects() {
+        let frame = Polygon::<f32>::with_holes(
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(10.0, 0.0),
+                Vec2::new(10.0, 10.0),
+                Vec2::new(0.0, 10.0),
+            ],
+            vec![vec![
+                Vec2::new(3.0, 3.0),
+                Vec2::new(7.0, 3.0),
+                Vec2::new(7.0, 7.0),
+                Vec2::new(3.0, 7.0),
+            ]],
+        );
+
+        assert!(frame.contains_point(Vec2::new(1.0, 5.0)));
+        assert!(!frame.contains_point(Vec2::new(5.0, 5.0)));
+        assert!(!frame.contains_point(Vec2::new(11.0, 5.0)));
+
+        // Self-overlapping loops still count as inside under the non-zero rule
+        let doubled = Polygon::<f32>::new(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 4.0),
+            Vec2::new(0.0, 4.0),
+        ]);
+        assert!(doubled.contains_point(Vec2::new(2.0, 2.0)));
+
+        assert!(frame.intersects_rect(&Rect::from_corners(
+            Vec2::new(-2.0, -2.0),
+            Vec2::new(1.0, 1.0)
+        )));
+        assert!(frame.intersects_rect(&Rect::from_corners(
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 2.0)
+        )));
+        assert!(!frame.intersects_rect(&Rect::from_corners(
+            Vec2::new(4.0, 4.0),
+            Vec2::new(6.0, 6.0)
+        )));
+
+        assert!(frame.intersects_segment(&Segment::new(Vec2::new(5.0, 5.0), Vec2::new(5.0, 12.0))));
+        assert!(!frame.intersects_segment(&Segment::new(Vec2::new(4.0, 5.0), Vec2::new(6.0, 5.0))));
+
+        assert!(frame.intersects_circle(&Circle::new(Vec2::new(5.0, 5.0), 2.5)));
+        assert!(!frame.intersects_circle(&Circle::new(Vec2::new(5.0, 5.0), 1.5)));
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            UVec2::new(2, 2),
+            UVec2::new(0, 0),
+            UVec2::new(4, 0),
+            UVec2::new(2, 0),
+            UVec2::new(4, 4),
+            UVec2::new(1, 3),
+            UVec2::new(0, 4),
+            UVec2::new(4, 4),
+        ];
+
+        let hull = Polygon::<u32>::convex_hull(&points);
+
+        assert_eq!(
+            hull.exterior,
+            vec![
+                UVec2::new(0, 0),
+                UVec2::new(4, 0),
+                UVec2::new(4, 4),
+                UVec2::new(0, 4)
+            ]
+        );
+        assert_eq!(hull.winding(), Some(Winding::Clockwise));
+        assert!(points.iter().all(|&pos| hull.contains_point(pos)
+            || hull.edges().any(|edge| edge.distance_to_point(pos) == 0.0)));
+
+        assert_eq!(
+            Polygon::<u32>::convex_hull(&[UVec2::ONE, UVec2::ONE]).exterior,
+            vec![UVec2::ONE]
+        );
+    }
+}
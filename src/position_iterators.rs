@@ -0,0 +1,537 @@
+use crate::*;
+
+use core::{iter::FusedIterator, marker::PhantomData};
+
+/// Integer 2D vectors that positions can be iterated over
+pub trait GridVec: Copy {
+    fn from_xy(x: i64, y: i64) -> Self;
+    fn to_xy(self) -> (i64, i64);
+}
+
+impl GridVec for UVec2 {
+    #[inline]
+    fn from_xy(x: i64, y: i64) -> Self {
+        UVec2::new(x as u32, y as u32)
+    }
+
+    #[inline]
+    fn to_xy(self) -> (i64, i64) {
+        (self.x as i64, self.y as i64)
+    }
+}
+
+impl GridVec for IVec2 {
+    #[inline]
+    fn from_xy(x: i64, y: i64) -> Self {
+        IVec2::new(x as i32, y as i32)
+    }
+
+    #[inline]
+    fn to_xy(self) -> (i64, i64) {
+        (self.x as i64, self.y as i64)
+    }
+}
+
+/// Every position in a grid, in row-major (left-to-right & top-to-bottom) or column-major order
+///
+/// Positions are computed from an index, so this never allocates and supports iterating from both ends
+#[derive(Clone, Debug)]
+pub struct GridPositions<V> {
+    origin: (i64, i64),
+    width: u64,
+    height: u64,
+    column_major: bool,
+    front: u64,
+    back: u64,
+    _marker: PhantomData<V>,
+}
+
+impl<V: GridVec> GridPositions<V> {
+    /// Left-to-right, then top-to-bottom
+    #[inline]
+    pub fn row_major(top_left: V, size: UVec2) -> Self {
+        Self::new(top_left, size, false)
+    }
+
+    /// Top-to-bottom, then left-to-right
+    #[inline]
+    pub fn column_major(top_left: V, size: UVec2) -> Self {
+        Self::new(top_left, size, true)
+    }
+
+    fn new(top_left: V, size: UVec2, column_major: bool) -> Self {
+        Self {
+            origin: top_left.to_xy(),
+            width: size.x as u64,
+            height: size.y as u64,
+            column_major,
+            front: 0,
+            back: size.x as u64 * size.y as u64,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn pos_at(&self, idx: u64) -> V {
+        let (x, y) = if self.column_major {
+            (idx / self.height, idx % self.height)
+        } else {
+            (idx % self.width, idx / self.width)
+        };
+
+        V::from_xy(self.origin.0 + x as i64, self.origin.1 + y as i64)
+    }
+}
+
+impl<V: GridVec> Iterator for GridPositions<V> {
+    type Item = V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let pos = self.pos_at(self.front);
+        self.front += 1;
+
+        Some(pos)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n as u64).min(self.back);
+
+        self.next()
+    }
+}
+
+impl<V: GridVec> DoubleEndedIterator for GridPositions<V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        self.back -= 1;
+
+        Some(self.pos_at(self.back))
+    }
+}
+
+impl<V: GridVec> ExactSizeIterator for GridPositions<V> {}
+
+impl<V: GridVec> FusedIterator for GridPositions<V> {}
+
+/// The outermost positions of a grid, in row-major order
+///
+/// Only visits the border, so is O(perimeter) rather than O(area)
+#[derive(Clone, Debug)]
+pub struct BorderPositions<V> {
+    origin: (i64, i64),
+    width: u64,
+    height: u64,
+    front: u64,
+    back: u64,
+    _marker: PhantomData<V>,
+}
+
+impl<V: GridVec> BorderPositions<V> {
+    pub fn new(top_left: V, size: UVec2) -> Self {
+        let (width, height) = (size.x as u64, size.y as u64);
+
+        let len = match (width, height) {
+            (0, _) | (_, 0) => 0,
+            (_, 1) => width,
+            _ => width * 2 + (height - 2) * width.min(2),
+        };
+
+        Self {
+            origin: top_left.to_xy(),
+            width,
+            height,
+            front: 0,
+            back: len,
+            _marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn pos_at(&self, idx: u64) -> V {
+        // The top row, then the left & right cells of each middle row, then the bottom row
+        let per_row = self.width.min(2);
+        let middle = self.height.saturating_sub(2) * per_row;
+
+        let (x, y) = if idx < self.width {
+            (idx, 0)
+        } else if idx < self.width + middle {
+            let k = idx - self.width;
+            // Alternates left then right, unless the grid is a single column
+            ((k % per_row) * (self.width - 1), 1 + k / per_row)
+        } else {
+            (idx - self.width - middle, self.height - 1)
+        };
+
+        V::from_xy(self.origin.0 + x as i64, self.origin.1 + y as i64)
+    }
+}
+
+impl<V: GridVec> Iterator for BorderPositions<V> {
+    type Item = V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let pos = self.pos_at(self.front);
+        self.front += 1;
+
+        Some(pos)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+
+        (len, Some(len))
+    }
+}
+
+impl<V: GridVec> DoubleEndedIterator for BorderPositions<V> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        self.back -= 1;
+
+        Some(self.pos_at(self.back))
+    }
+}
+
+impl<V: GridVec> ExactSizeIterator for BorderPositions<V> {}
+
+impl<V: GridVec> FusedIterator for BorderPositions<V> {}
+
+/// Every position of a grid, spiralling outwards from the center
+///
+/// Each ring starts at its top left corner and goes clockwise (with y pointing down).
+/// For even sizes the center is rounded towards the top left.
+/// Ring sides outside the grid are skipped in O(1), so thin grids stay O(area)
+#[derive(Clone, Debug)]
+pub struct SpiralPositions<V> {
+    /// Inclusive
+    min: (i64, i64),
+    /// Inclusive
+    max: (i64, i64),
+    center: (i64, i64),
+    front: RingCursor,
+    back: RingCursor,
+    remaining: usize,
+    _marker: PhantomData<V>,
+}
+
+/// Position along a ring, `side` 0..4 is top, right, bottom, left
+#[derive(Copy, Clone, Debug)]
+struct RingCursor {
+    ring: i64,
+    side: u8,
+    t: i64,
+}
+
+impl<V: GridVec> SpiralPositions<V> {
+    pub fn new(top_left: V, size: UVec2) -> Self {
+        let min = top_left.to_xy();
+        let max = (min.0 + size.x as i64 - 1, min.1 + size.y as i64 - 1);
+        let center = (
+            min.0 + (size.x as i64 - 1) / 2,
+            min.1 + (size.y as i64 - 1) / 2,
+        );
+
+        let max_ring = (center.0 - min.0)
+            .max(max.0 - center.0)
+            .max(center.1 - min.1)
+            .max(max.1 - center.1);
+
+        Self {
+            min,
+            max,
+            center,
+            front: RingCursor {
+                ring: 0,
+                side: 0,
+                t: 0,
+            },
+            back: RingCursor {
+                ring: max_ring,
+                side: 3,
+                t: i64::MAX,
+            },
+            remaining: size.x as usize * size.y as usize,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Start of a ring side and the direction it runs in
+    #[inline]
+    fn side_start(&self, ring: i64, side: u8) -> ((i64, i64), (i64, i64)) {
+        let (cx, cy) = self.center;
+
+        match side {
+            0 => ((cx - ring, cy - ring), (1, 0)),
+            1 => ((cx + ring, cy - ring), (0, 1)),
+            2 => ((cx + ring, cy + ring), (-1, 0)),
+            _ => ((cx - ring, cy + ring), (0, -1)),
+        }
+    }
+
+    /// Inclusive range of `t` along a ring side that's inside the grid
+    ///
+    /// Each side is `2 * ring` long, and stops before the next side's first corner
+    fn side_range(&self, ring: i64, side: u8) -> Option<(i64, i64)> {
+        if ring == 0 {
+            // The center is always inside, on its own
+            return (side == 0).then_some((0, 0));
+        }
+
+        let ((sx, sy), (dx, dy)) = self.side_start(ring, side);
+        let (mut lo, mut hi) = (0, ring * 2 - 1);
+
+        for (start, dir, min, max) in [
+            (sx, dx, self.min.0, self.max.0),
+            (sy, dy, self.min.1, self.max.1),
+        ] {
+            match dir {
+                0 if start < min || start > max => return None,
+                0 => {}
+                1 => {
+                    lo = lo.max(min - start);
+                    hi = hi.min(max - start);
+                }
+                _ => {
+                    lo = lo.max(start - max);
+                    hi = hi.min(start - min);
+                }
+            }
+        }
+
+        (lo <= hi).then_some((lo, hi))
+    }
+
+    #[inline]
+    fn pos_at(&self, ring: i64, side: u8, t: i64) -> V {
+        let ((sx, sy), (dx, dy)) = self.side_start(ring, side);
+
+        V::from_xy(sx + dx * t, sy + dy * t)
+    }
+}
+
+impl<V: GridVec> Iterator for SpiralPositions<V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        // There is always an unvisited position ahead, so this terminates
+        loop {
+            let RingCursor { ring, side, t } = self.front;
+
+            if let Some((lo, hi)) = self.side_range(ring, side) {
+                let t = t.max(lo);
+
+                if t <= hi {
+                    self.front.t = t + 1;
+                    self.remaining -= 1;
+
+                    return Some(self.pos_at(ring, side, t));
+                }
+            }
+
+            self.front = match side {
+                3 => RingCursor {
+                    ring: ring + 1,
+                    side: 0,
+                    t: 0,
+                },
+                _ => RingCursor {
+                    ring,
+                    side: side + 1,
+                    t: 0,
+                },
+            };
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<V: GridVec> DoubleEndedIterator for SpiralPositions<V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        loop {
+            let RingCursor { ring, side, t } = self.back;
+
+            if let Some((lo, hi)) = self.side_range(ring, side) {
+                let t = t.min(hi);
+
+                if t >= lo {
+                    self.back.t = t - 1;
+                    self.remaining -= 1;
+
+                    return Some(self.pos_at(ring, side, t));
+                }
+            }
+
+            self.back = match side {
+                0 => RingCursor {
+                    ring: ring - 1,
+                    side: 3,
+                    t: i64::MAX,
+                },
+                _ => RingCursor {
+                    ring,
+                    side: side - 1,
+                    t: i64::MAX,
+                },
+            };
+        }
+    }
+}
+
+impl<V: GridVec> ExactSizeIterator for SpiralPositions<V> {}
+
+impl<V: GridVec> FusedIterator for SpiralPositions<V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_positions() {
+        let size = UVec2::new(3, 2);
+        let row_major = GridPositions::row_major(UVec2::new(1, 1), size);
+
+        assert_eq!(row_major.len(), 6);
+        assert_eq!(
+            row_major.clone().collect_vec(),
+            vec![
+                UVec2::new(1, 1),
+                UVec2::new(2, 1),
+                UVec2::new(3, 1),
+                UVec2::new(1, 2),
+                UVec2::new(2, 2),
+                UVec2::new(3, 2),
+            ]
+        );
+        assert_eq!(
+            row_major.clone().rev().collect_vec(),
+            row_major
+                .clone()
+                .collect_vec()
+                .into_iter()
+                .rev()
+                .collect_vec()
+        );
+
+        let mut column_major = GridPositions::column_major(IVec2::new(-1, 0), size);
+
+        assert_eq!(column_major.next(), Some(IVec2::new(-1, 0)));
+        assert_eq!(column_major.next_back(), Some(IVec2::new(1, 1)));
+        assert_eq!(column_major.next(), Some(IVec2::new(-1, 1)));
+        assert_eq!(column_major.len(), 3);
+
+        assert_eq!(
+            GridPositions::row_major(UVec2::ZERO, UVec2::new(4, 0)).len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_border_positions() {
+        for size in [
+            UVec2::new(5, 4),
+            UVec2::new(1, 4),
+            UVec2::new(4, 1),
+            UVec2::new(2, 2),
+            UVec2::new(3, 0),
+        ] {
+            let expected = GridPositions::row_major(UVec2::ZERO, size)
+                .filter(|pos| {
+                    pos.x == 0 || pos.y == 0 || pos.x == size.x - 1 || pos.y == size.y - 1
+                })
+                .collect_vec();
+
+            let border = BorderPositions::new(UVec2::ZERO, size);
+
+            assert_eq!(border.len(), expected.len());
+            assert_eq!(border.clone().collect_vec(), expected);
+            assert_eq!(
+                border.rev().collect_vec(),
+                expected.into_iter().rev().collect_vec()
+            );
+        }
+    }
+
+    #[test]
+    fn test_spiral_positions() {
+        for size in [
+            UVec2::new(5, 5),
+            UVec2::new(4, 3),
+            UVec2::new(1, 7),
+            UVec2::new(9, 2),
+        ] {
+            let top_left = IVec2::new(-2, 3);
+            let spiral = SpiralPositions::new(top_left, size);
+            let positions = spiral.clone().collect_vec();
+
+            assert_eq!(spiral.len(), positions.len());
+            assert_eq!(
+                positions.iter().sorted_by_key(|p| (p.y, p.x)).collect_vec(),
+                GridPositions::row_major(top_left, size)
+                    .collect_vec()
+                    .iter()
+                    .collect_vec()
+            );
+            assert_eq!(
+                spiral.rev().collect_vec(),
+                positions.iter().copied().rev().collect_vec()
+            );
+
+            // Rings only get further from the center
+            let center = positions[0];
+            assert!(
+                positions
+                    .iter()
+                    .map(|p| (*p - center).abs().max_element())
+                    .tuple_windows()
+                    .all(|(a, b)| a <= b)
+            );
+        }
+
+        let mut spiral = SpiralPositions::new(UVec2::ZERO, UVec2::splat(3));
+
+        assert_eq!(spiral.next(), Some(UVec2::new(1, 1)));
+        assert_eq!(spiral.next(), Some(UVec2::new(0, 0)));
+        assert_eq!(spiral.next(), Some(UVec2::new(1, 0)));
+        assert_eq!(spiral.next_back(), Some(UVec2::new(0, 1)));
+        assert_eq!(spiral.len(), 5);
+    }
+}
@@ -1,9 +1,16 @@
 use crate::*;
 
+use core::fmt;
+
+/// A half-open Rect, `top_left` is inside and `bottom_right` is one past the last cell
+///
+/// `size`, `area`, `positions`, `border_positions` and `contains_pos` all share this definition.
+/// Rects with a zero width or height are valid and empty. See `ClosedRect` for a Rect with both corners inside
 #[cfg_attr(feature = "bevy", derive(Reflect))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Rect<T: RectScalar> {
     pub top_left: T::V2,
+    /// Exclusive
     pub bottom_right: T::V2,
 }
 
@@ -22,9 +29,11 @@ impl<T: RectScalar> Rect<T> {
         }
     }
 
+    /// Panics if `bottom_right` is above or left of `top_left`, equal coordinates give an empty Rect
     pub fn from_corners(top_left: T::V2, bottom_right: T::V2) -> Self {
         assert!(
-            T::v2_x(top_left) < T::v2_x(bottom_right) && T::v2_y(top_left) < T::v2_y(bottom_right),
+            T::v2_x(top_left) <= T::v2_x(bottom_right)
+                && T::v2_y(top_left) <= T::v2_y(bottom_right),
             "{top_left}, {bottom_right}"
         );
 
@@ -34,73 +43,6 @@ impl<T: RectScalar> Rect<T> {
         }
     }
 
-    #[inline]
-    pub fn bounds_from_positions(positions: &[T::V2]) -> Self {
-        let mut min_x = T::max();
-        let mut min_y = T::max();
-        let mut max_x = T::zero();
-        let mut max_y = T::zero();
-
-        for &pos in positions {
-            if T::v2_x(pos) < min_x {
-                min_x = T::v2_x(pos);
-            }
-            if T::v2_y(pos) < min_y {
-                min_y = T::v2_y(pos);
-            }
-            if T::v2_x(pos) > max_x {
-                max_x = T::v2_x(pos);
-            }
-            if T::v2_y(pos) > max_y {
-                max_y = T::v2_y(pos);
-            }
-        }
-
-        debug_assert!(
-            max_x > min_x && max_y > min_y,
-            "Invalid bounding rect\nmin_x: {}, max_x: {}\nmin_y: {}, max_y: {}",
-            min_x,
-            max_x,
-            min_y,
-            max_y
-        );
-
-        Self::from_corners(T::v2_new(min_x, min_y), T::v2_new(max_x, max_y))
-    }
-
-    /// TODO - change to Result ?
-    #[inline]
-    pub fn bounds_from_positions_checked(positions: &[T::V2]) -> Option<Self> {
-        let mut min_x = T::max();
-        let mut min_y = T::max();
-        let mut max_x = T::zero();
-        let mut max_y = T::zero();
-
-        for &pos in positions {
-            if T::v2_x(pos) < min_x {
-                min_x = T::v2_x(pos);
-            }
-            if T::v2_y(pos) < min_y {
-                min_y = T::v2_y(pos);
-            }
-            if T::v2_x(pos) > max_x {
-                max_x = T::v2_x(pos);
-            }
-            if T::v2_y(pos) > max_y {
-                max_y = T::v2_y(pos);
-            }
-        }
-
-        if !(max_x > min_x && max_y > min_y) {
-            return None;
-        }
-
-        Some(Self::from_corners(
-            T::v2_new(min_x, min_y),
-            T::v2_new(max_x, max_y),
-        ))
-    }
-
     /// Top left, Top right, Bottom left, Bottom right
     #[inline]
     pub fn corners(&self) -> [T::V2; 4] {
@@ -162,12 +104,30 @@ impl<T: RectScalar> Rect<T> {
     //     if self.width() > self.height()
     // }
 
+    /// Exclusive of `bottom_right`, matching `positions()`
     #[inline]
     pub fn contains_pos(&self, pos: T::V2) -> bool {
         T::v2_x(pos) >= T::v2_x(self.top_left)
             && T::v2_y(pos) >= T::v2_y(self.top_left)
-            && T::v2_x(pos) <= T::v2_x(self.bottom_right)
-            && T::v2_y(pos) <= T::v2_y(self.bottom_right)
+            && T::v2_x(pos) < T::v2_x(self.bottom_right)
+            && T::v2_y(pos) < T::v2_y(self.bottom_right)
+    }
+}
+
+impl<T: IntegerScalar> Rect<T> {
+    /// Closed equivalent, with `bottom_right` on the last cell
+    ///
+    /// None if Self is empty, as a ClosedRect always contains at least one cell
+    #[inline]
+    pub fn to_closed(&self) -> Option<ClosedRect<T>> {
+        if self.is_empty() {
+            return None;
+        }
+
+        Some(ClosedRect {
+            top_left: self.top_left,
+            bottom_right: self.bottom_right - T::one(),
+        })
     }
 }
 
@@ -255,118 +215,449 @@ impl<T: RectScalar> Rect<T> {
         self.bottom_right /= scale_factor;
     }
 
-    pub fn bisect_at(&self, orientation: Orientation, split_point: T) -> (Self, Self) {
-        // let rect_1;
-        // let rect_2;
-
-        // match orientation {
-        //     Orientation::Vertical => {
-        //         let tl_1 = self.top_left;
-        //         let br_1 = T::v2_new(T::v2_x(tl_1) + split_point, T::v2_y(self.bottom_right));
-
-        //         let tl_2 = T::v2_new(
-        //             T::v2_x(tl_1) + split_point + T::one(),
-        //             T::v2_y(self.top_left),
-        //         );
-        //         let br_2 = self.bottom_right;
-
-        //         rect_1 = Self::from_corners(tl_1, br_1);
-        //         rect_2 = Self::from_corners(tl_2, br_2);
-        //     }
-        //     Orientation::Horizontal => {
-        //         let tl_1 = self.top_left;
-        //         let br_1 = T::v2_new(T::v2_x(self.bottom_right), T::v2_y(tl_1) + split_point);
+    /// Splits Self into two Rects, with the split line `split_point` along from `top_left`
+    ///
+    /// `Vertical` splits with a vertical line (left & right halves), `Horizontal` with a horizontal line (top & bottom halves).
+    /// The halves share the split edge, matching the exclusive `bottom_right` of `positions()`
+    ///
+    /// Errors if `orientation` is diagonal, or if either half would be empty
+    pub fn bisect_at(
+        &self,
+        orientation: Orientation,
+        split_point: T,
+    ) -> Result<(Self, Self), RectError> {
+        let length = match orientation {
+            Orientation::Vertical => self.width(),
+            Orientation::Horizontal => self.height(),
+            _ => return Err(RectError::DiagonalOrientation(orientation)),
+        };
+
+        if split_point <= T::zero() || split_point >= length {
+            return Err(RectError::SplitOutOfBounds);
+        }
 
-        //         let tl_2 = T::v2_new(
-        //             T::v2_x(self.top_left),
-        //             T::v2_y(tl_1) + split_point + T::one(),
-        //         );
-        //         let br_2 = self.bottom_right;
+        let (br_1, tl_2) = match orientation {
+            Orientation::Vertical => {
+                let split_x = T::v2_x(self.top_left) + split_point;
 
-        //         rect_1 = Self::from_corners(tl_1, br_1);
-        //         rect_2 = Self::from_corners(tl_2, br_2);
-        //     }
-        // }
+                (
+                    T::v2_new(split_x, T::v2_y(self.bottom_right)),
+                    T::v2_new(split_x, T::v2_y(self.top_left)),
+                )
+            }
+            _ => {
+                let split_y = T::v2_y(self.top_left) + split_point;
 
-        // (rect_1, rect_2)
+                (
+                    T::v2_new(T::v2_x(self.bottom_right), split_y),
+                    T::v2_new(T::v2_x(self.top_left), split_y),
+                )
+            }
+        };
 
-        todo!()
+        Ok((
+            Self::from_corners(self.top_left, br_1),
+            Self::from_corners(tl_2, self.bottom_right),
+        ))
     }
 
+    /// Distance from `top_left` at `length_percent` of the side length
+    ///
+    /// `Vertical` measures along the height, `Horizontal` along the width
+    ///
+    /// Errors if `length_orientation` is diagonal
     pub fn get_local_coord_at_length_percent(
         &self,
         length_orientation: Orientation,
         length_percent: f32,
-    ) -> T {
-        // match length_orientation {
-        //     Orientation::Vertical => T::from_f32(T::v2_y(self.size()).to_f32() * length_percent),
-        //     Orientation::Horizontal => T::from_f32(T::v2_x(self.size()).to_f32() * length_percent),
-        // }
-        todo!()
+    ) -> Result<T, RectError> {
+        match length_orientation {
+            Orientation::Vertical => Ok(T::from_f32(self.height().to_f32() * length_percent)),
+            Orientation::Horizontal => Ok(T::from_f32(self.width().to_f32() * length_percent)),
+            _ => Err(RectError::DiagonalOrientation(length_orientation)),
+        }
     }
 
+    /// Global equivalent of `get_local_coord_at_length_percent`
+    ///
+    /// Returns a y coordinate for `Vertical`, and an x coordinate for `Horizontal`
     pub fn get_global_coord_at_length_percent(
         &self,
         length_orientation: Orientation,
         length_percent: f32,
-    ) -> T {
-        // match length_orientation {
-        //     Orientation::Vertical => {
-        //         T::v2_x(self.top_left) + T::from_f32(T::v2_y(self.size()).to_f32() * length_percent)
-        //     }
-        //     Orientation::Horizontal => {
-        //         T::v2_y(self.top_left) + T::from_f32(T::v2_x(self.size()).to_f32() * length_percent)
-        //     }
-        // }
-        todo!()
+    ) -> Result<T, RectError> {
+        let local = self.get_local_coord_at_length_percent(length_orientation, length_percent)?;
+
+        match length_orientation {
+            Orientation::Vertical => Ok(T::v2_y(self.top_left) + local),
+            _ => Ok(T::v2_x(self.top_left) + local),
+        }
     }
 
+    /// `bisect_at`, with the split point at `length_percent` across the Rect
+    ///
+    /// eg. `Vertical`, 0.25 = split with a vertical line, a quarter of the width from the left edge
     pub fn bisect_at_length_percent(
         &self,
         orientation: Orientation,
         length_percent: f32,
-    ) -> (Self, Self) {
-        // let rect_1;
-        // let rect_2;
-
-        // match orientation {
-        //     Orientation::Vertical => {
-        //         let split_point = T::from_f32(T::v2_x(self.size()).to_f32() * length_percent);
-
-        //         let tl_1 = self.top_left;
-        //         let br_1 = T::v2_new(T::v2_x(tl_1) + split_point, T::v2_y(self.bottom_right));
-
-        //         let tl_2 = T::v2_new(
-        //             T::v2_x(tl_1) + split_point + T::one(),
-        //             T::v2_y(self.top_left),
-        //         );
-        //         let br_2 = self.bottom_right;
-
-        //         rect_1 = Self::from_corners(tl_1, br_1);
-        //         rect_2 = Self::from_corners(tl_2, br_2);
-        //     }
-        //     Orientation::Horizontal => {
-        //         let split_point = T::from_f32(T::v2_y(self.size()).to_f32() * length_percent);
-
-        //         let tl_1 = self.top_left;
-        //         let br_1 = T::v2_new(T::v2_x(self.bottom_right), T::v2_y(tl_1) + split_point);
-
-        //         let tl_2 = T::v2_new(
-        //             T::v2_x(self.top_left),
-        //             T::v2_y(tl_1) + split_point + T::one(),
-        //         );
-        //         let br_2 = self.bottom_right;
-
-        //         rect_1 = Self::from_corners(tl_1, br_1);
-        //         rect_2 = Self::from_corners(tl_2, br_2);
-        //     }
-        // }
-
-        // (rect_1, rect_2)
-        todo!()
+    ) -> Result<(Self, Self), RectError> {
+        if !matches!(orientation, Orientation::Vertical | Orientation::Horizontal) {
+            return Err(RectError::DiagonalOrientation(orientation));
+        }
+
+        // A vertical split line cuts across the width
+        let split_point =
+            self.get_local_coord_at_length_percent(orientation.opposite(), length_percent)?;
+
+        self.bisect_at(orientation, split_point)
     }
 }
 
+/// Set operations
+///
+/// These treat Rects as half-open regions, `top_left` is inside and `bottom_right` is outside,
+/// matching `positions()`. Rects that only touch along an edge don't overlap, and a Rect with a zero width or height is empty.
+/// The point queries (`clamp_point`, `distance_to_point`, `closest_point`) follow the same edges:
+/// with integer scalars they stay within the last row and column of `positions()`,
+/// with float scalars they measure to the continuous region between the corners
+impl<T: RectScalar> Rect<T> {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        T::v2_x(self.top_left) >= T::v2_x(self.bottom_right)
+            || T::v2_y(self.top_left) >= T::v2_y(self.bottom_right)
+    }
+
+    /// True if the Rects share any area, touching edges don't count
+    #[inline]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        T::v2_x(self.top_left) < T::v2_x(other.bottom_right)
+            && T::v2_x(other.top_left) < T::v2_x(self.bottom_right)
+            && T::v2_y(self.top_left) < T::v2_y(other.bottom_right)
+            && T::v2_y(other.top_left) < T::v2_y(self.bottom_right)
+    }
+
+    /// The overlapping area, None if the Rects don't overlap
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if !self.overlaps(other) {
+            return None;
+        }
+
+        Some(Self {
+            top_left: T::v2_new(
+                max_scalar(T::v2_x(self.top_left), T::v2_x(other.top_left)),
+                max_scalar(T::v2_y(self.top_left), T::v2_y(other.top_left)),
+            ),
+            bottom_right: T::v2_new(
+                min_scalar(T::v2_x(self.bottom_right), T::v2_x(other.bottom_right)),
+                min_scalar(T::v2_y(self.bottom_right), T::v2_y(other.bottom_right)),
+            ),
+        })
+    }
+
+    /// True if every position of `other` is inside Self, edges may be shared
+    #[inline]
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        T::v2_x(other.top_left) >= T::v2_x(self.top_left)
+            && T::v2_y(other.top_left) >= T::v2_y(self.top_left)
+            && T::v2_x(other.bottom_right) <= T::v2_x(self.bottom_right)
+            && T::v2_y(other.bottom_right) <= T::v2_y(self.bottom_right)
+    }
+
+    /// The smallest Rect containing both Rects
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            top_left: T::v2_new(
+                min_scalar(T::v2_x(self.top_left), T::v2_x(other.top_left)),
+                min_scalar(T::v2_y(self.top_left), T::v2_y(other.top_left)),
+            ),
+            bottom_right: T::v2_new(
+                max_scalar(T::v2_x(self.bottom_right), T::v2_x(other.bottom_right)),
+                max_scalar(T::v2_y(self.bottom_right), T::v2_y(other.bottom_right)),
+            ),
+        }
+    }
+
+    /// The area of Self not covered by `other`, as up to 4 disjoint Rects
+    ///
+    /// The top and bottom pieces span the full width, the left and right pieces fill the gap between them.
+    /// Returns Self if the Rects don't overlap, and nothing if `other` covers Self or Self is empty
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let Some(cut) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        let (left, top) = (T::v2_x(self.top_left), T::v2_y(self.top_left));
+        let (right, bottom) = (T::v2_x(self.bottom_right), T::v2_y(self.bottom_right));
+        let (cut_left, cut_top) = (T::v2_x(cut.top_left), T::v2_y(cut.top_left));
+        let (cut_right, cut_bottom) = (T::v2_x(cut.bottom_right), T::v2_y(cut.bottom_right));
+
+        let mut pieces = Vec::with_capacity(4);
+
+        if cut_top > top {
+            pieces.push(Self {
+                top_left: self.top_left,
+                bottom_right: T::v2_new(right, cut_top),
+            });
+        }
+        if cut_bottom < bottom {
+            pieces.push(Self {
+                top_left: T::v2_new(left, cut_bottom),
+                bottom_right: self.bottom_right,
+            });
+        }
+        if cut_left > left {
+            pieces.push(Self {
+                top_left: T::v2_new(left, cut_top),
+                bottom_right: T::v2_new(cut_left, cut_bottom),
+            });
+        }
+        if cut_right < right {
+            pieces.push(Self {
+                top_left: T::v2_new(cut_right, cut_top),
+                bottom_right: T::v2_new(right, cut_bottom),
+            });
+        }
+
+        pieces
+    }
+
+    /// The closest point to `pos` inside the Rect, `pos` itself if it's already inside
+    ///
+    /// Integer Rects clamp to `bottom_right - 1`, so the result is one of `positions()`. Empty Rects clamp to `top_left`
+    #[inline]
+    pub fn clamp_point(&self, pos: T::V2) -> T::V2 {
+        let last = self.last_point();
+
+        T::v2_new(
+            clamp_scalar(T::v2_x(pos), T::v2_x(self.top_left), T::v2_x(last)),
+            clamp_scalar(T::v2_y(pos), T::v2_y(self.top_left), T::v2_y(last)),
+        )
+    }
+
+    /// The largest point the point queries treat as inside, `bottom_right` for floats
+    /// and `bottom_right - 1` for integers
+    #[inline]
+    pub(crate) fn last_point(&self) -> T::V2 {
+        let last = |top_left: T, bottom_right: T| {
+            if !T::IS_INTEGER {
+                max_scalar(top_left, bottom_right)
+            } else if bottom_right > top_left {
+                bottom_right - T::one()
+            } else {
+                top_left
+            }
+        };
+
+        T::v2_new(
+            last(T::v2_x(self.top_left), T::v2_x(self.bottom_right)),
+            last(T::v2_y(self.top_left), T::v2_y(self.bottom_right)),
+        )
+    }
+
+    /// Euclidian distance from `pos` to `clamp_point(pos)`, 0 if `pos` is inside
+    #[inline]
+    pub fn distance_to_point(&self, pos: T::V2) -> f32 {
+        let clamped = self.clamp_point(pos);
+
+        let dx = abs_diff(T::v2_x(pos), T::v2_x(clamped)).to_f32();
+        let dy = abs_diff(T::v2_y(pos), T::v2_y(clamped)).to_f32();
+
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// The closest point to `pos` on the edge of the Rect, for integer Rects a cell of `border_positions()`
+    ///
+    /// Same as `clamp_point` for positions outside, positions inside are moved to the nearest edge
+    pub fn closest_point(&self, pos: T::V2) -> T::V2 {
+        let clamped = self.clamp_point(pos);
+
+        if clamped != pos {
+            return clamped;
+        }
+
+        let (x, y) = (T::v2_x(pos), T::v2_y(pos));
+        let (left, top) = (T::v2_x(self.top_left), T::v2_y(self.top_left));
+        let last = self.last_point();
+        let (right, bottom) = (T::v2_x(last), T::v2_y(last));
+
+        // Ties go to the left, then top, then right edge
+        [
+            (x - left, T::v2_new(left, y)),
+            (y - top, T::v2_new(x, top)),
+            (right - x, T::v2_new(right, y)),
+            (bottom - y, T::v2_new(x, bottom)),
+        ]
+        .into_iter()
+        .fold(
+            None,
+            |closest: Option<(T, T::V2)>, (dist, point)| match closest {
+                Some((closest_dist, _)) if closest_dist <= dist => closest,
+                _ => Some((dist, point)),
+            },
+        )
+        .map(|(_, point)| point)
+        .unwrap_or(pos)
+    }
+}
+
+/// How float coordinates are rounded when converting a Rect to an integer scalar
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    /// To the nearest integer, halves away from zero
+    Round,
+    /// Floors `top_left` and ceils `bottom_right`, so the result always covers the original Rect
+    Outward,
+}
+
+impl Rounding {
+    #[inline]
+    fn apply(self, value: f64, is_bottom_right: bool) -> f64 {
+        match self {
+            Rounding::Floor => value.floor(),
+            Rounding::Ceil => value.ceil(),
+            Rounding::Round => value.round(),
+            Rounding::Outward if is_bottom_right => value.ceil(),
+            Rounding::Outward => value.floor(),
+        }
+    }
+}
+
+/// Conversions between scalar types
+impl<T: RectScalar> Rect<T> {
+    /// Converts each coordinate like `as`, floats truncate towards zero and out of range values saturate
+    ///
+    /// Integer to integer conversions are exact whenever the value fits, however large
+    #[inline]
+    pub fn cast<B: RectScalar>(&self) -> Rect<B> {
+        let cast = |pos: T::V2| B::v2_new(cast_scalar(T::v2_x(pos)), cast_scalar(T::v2_y(pos)));
+
+        Rect {
+            top_left: cast(self.top_left),
+            bottom_right: cast(self.bottom_right),
+        }
+    }
+
+    /// Lossless conversion, None if any coordinate can't be represented exactly
+    ///
+    /// eg. negative or fractional coordinates when converting to an unsigned Rect,
+    /// or `i64` coordinates beyond ±2^53 when converting to `f64`
+    pub fn try_cast<B: RectScalar>(&self) -> Option<Rect<B>> {
+        let try_cast = |pos: T::V2| {
+            Some(B::v2_new(
+                try_cast_scalar(T::v2_x(pos))?,
+                try_cast_scalar(T::v2_y(pos))?,
+            ))
+        };
+
+        Some(Rect {
+            top_left: try_cast(self.top_left)?,
+            bottom_right: try_cast(self.bottom_right)?,
+        })
+    }
+
+    /// Rounds each coordinate with `rounding`, then converts like `cast`
+    ///
+    /// Integer coordinates are unaffected by rounding
+    pub fn convert<B: RectScalar>(&self, rounding: Rounding) -> Rect<B> {
+        let convert = |value: T, is_bottom_right: bool| match value.to_i128() {
+            Some(_) => cast_scalar(value),
+            None => B::from_f64(rounding.apply(value.to_f64(), is_bottom_right)),
+        };
+
+        let convert_pos = |pos: T::V2, is_bottom_right: bool| {
+            B::v2_new(
+                convert(T::v2_x(pos), is_bottom_right),
+                convert(T::v2_y(pos), is_bottom_right),
+            )
+        };
+
+        Rect {
+            top_left: convert_pos(self.top_left, false),
+            bottom_right: convert_pos(self.bottom_right, true),
+        }
+    }
+}
+
+/// Exact between integers that fit, otherwise through f64 which saturates
+#[inline]
+fn cast_scalar<A: RectScalar, B: RectScalar>(value: A) -> B {
+    match value.to_i128() {
+        Some(int) => B::from_i128(int).unwrap_or_else(|| B::from_f64(int as f64)),
+        None => B::from_f64(value.to_f64()),
+    }
+}
+
+fn try_cast_scalar<A: RectScalar, B: RectScalar>(value: A) -> Option<B> {
+    match value.to_i128() {
+        // Out of range for integers, so only floats can still hold it exactly
+        Some(int) => B::from_i128(int).or_else(|| {
+            let float = int as f64;
+            let converted = B::from_f64(float);
+
+            (converted.to_i128().is_none() && float as i128 == int && converted.to_f64() == float)
+                .then_some(converted)
+        }),
+        None => {
+            let float = value.to_f64();
+            let converted = B::from_f64(float);
+
+            (converted.to_f64() == float).then_some(converted)
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn min_scalar<T: RectScalar>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+#[inline]
+pub(crate) fn max_scalar<T: RectScalar>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+#[inline]
+fn clamp_scalar<T: RectScalar>(value: T, min: T, max: T) -> T {
+    max_scalar(min, min_scalar(value, max))
+}
+
+/// Never underflows for unsigned scalars
+#[inline]
+fn abs_diff<T: RectScalar>(a: T, b: T) -> T {
+    if a > b { a - b } else { b - a }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RectError {
+    /// Only `Vertical` and `Horizontal` are valid for this operation
+    DiagonalOrientation(Orientation),
+    /// The split point is not strictly inside the Rect
+    SplitOutOfBounds,
+    /// The Rect has a zero width or height
+    Empty,
+}
+
+impl fmt::Display for RectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RectError::DiagonalOrientation(orientation) => {
+                write!(f, "Orientation must not be diagonal (got {orientation})")
+            }
+            RectError::SplitOutOfBounds => write!(f, "Split point must be inside the Rect"),
+            RectError::Empty => write!(f, "Rect is empty"),
+        }
+    }
+}
+
+impl std::error::Error for RectError {}
+
 // impl<T: RectScalar> PartialOrd for Rect<T> {
 //     #[inline]
 //     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
@@ -385,40 +676,57 @@ impl Rect<u32> {
     /// Exclusive
     #[inline]
     pub fn positions(&self) -> Vec<UVec2> {
-        let mut positions = Vec::new();
+        self.iter_positions().collect()
+    }
 
-        for y in self.top_left.y..self.bottom_right.y {
-            for x in self.top_left.x..self.bottom_right.x {
-                positions.push(UVec2::new(x, y));
-            }
-        }
+    /// Also includes the row and column at `bottom_right`, which are outside Self
+    ///
+    /// Prefer `ClosedRect::positions` when `bottom_right` is meant to be inside
+    #[inline]
+    pub fn positions_inclusive(&self) -> Vec<UVec2> {
+        // An inverted axis has no cells, even with the extra row & column
+        let size = UVec2::select(
+            self.bottom_right.cmpge(self.top_left),
+            self.grid_size() + UVec2::ONE,
+            UVec2::ZERO,
+        );
 
-        positions
+        GridPositions::row_major(self.top_left, size).collect()
     }
 
+    /// Cells of `positions()` on the outermost row and column on each side, in the same order
     #[inline]
-    pub fn positions_inclusive(&self) -> Vec<UVec2> {
-        let mut positions = Vec::new();
+    pub fn border_positions(&self) -> Vec<UVec2> {
+        self.iter_border_positions().collect()
+    }
 
-        for y in self.top_left.y..=self.bottom_right.y {
-            for x in self.top_left.x..=self.bottom_right.x {
-                positions.push(UVec2::new(x, y));
-            }
-        }
+    /// Lazy version of `positions()`, row-major
+    #[inline]
+    pub fn iter_positions(&self) -> GridPositions<UVec2> {
+        GridPositions::row_major(self.top_left, self.grid_size())
+    }
 
-        positions
+    #[inline]
+    pub fn iter_positions_column_major(&self) -> GridPositions<UVec2> {
+        GridPositions::column_major(self.top_left, self.grid_size())
     }
 
-    /// TODO - improve performance
+    /// Spirals outwards from the center, see `SpiralPositions`
     #[inline]
-    pub fn border_positions(&self) -> Vec<UVec2> {
-        let valid_xs = [self.top_left.x, self.bottom_right.x];
-        let valid_ys = [self.top_left.y, self.bottom_right.y];
+    pub fn iter_positions_spiral(&self) -> SpiralPositions<UVec2> {
+        SpiralPositions::new(self.top_left, self.grid_size())
+    }
+
+    /// Lazy version of `border_positions()`
+    #[inline]
+    pub fn iter_border_positions(&self) -> BorderPositions<UVec2> {
+        BorderPositions::new(self.top_left, self.grid_size())
+    }
 
-        self.positions_inclusive()
-            .into_iter()
-            .filter(|&pos| valid_xs.contains(&pos.x) || valid_ys.contains(&pos.y))
-            .collect()
+    /// Size, or zero if the corners are the wrong way round
+    #[inline]
+    fn grid_size(&self) -> UVec2 {
+        self.bottom_right.saturating_sub(self.top_left)
     }
 }
 
@@ -426,39 +734,256 @@ impl Rect<i32> {
     /// Exclusive
     #[inline]
     pub fn positions(&self) -> Vec<IVec2> {
-        let mut positions = Vec::new();
+        self.iter_positions().collect()
+    }
 
-        for y in self.top_left.y..self.bottom_right.y {
-            for x in self.top_left.x..self.bottom_right.x {
-                positions.push(IVec2::new(x, y));
-            }
-        }
+    /// Also includes the row and column at `bottom_right`, which are outside Self
+    ///
+    /// Prefer `ClosedRect::positions` when `bottom_right` is meant to be inside
+    #[inline]
+    pub fn positions_inclusive(&self) -> Vec<IVec2> {
+        // An inverted axis has no cells, even with the extra row & column
+        let size = UVec2::select(
+            self.bottom_right.cmpge(self.top_left),
+            self.grid_size() + UVec2::ONE,
+            UVec2::ZERO,
+        );
 
-        positions
+        GridPositions::row_major(self.top_left, size).collect()
     }
 
+    /// Cells of `positions()` on the outermost row and column on each side, in the same order
     #[inline]
-    pub fn positions_inclusive(&self) -> Vec<IVec2> {
-        let mut positions = Vec::new();
+    pub fn border_positions(&self) -> Vec<IVec2> {
+        self.iter_border_positions().collect()
+    }
+
+    /// Lazy version of `positions()`, row-major
+    #[inline]
+    pub fn iter_positions(&self) -> GridPositions<IVec2> {
+        GridPositions::row_major(self.top_left, self.grid_size())
+    }
+
+    #[inline]
+    pub fn iter_positions_column_major(&self) -> GridPositions<IVec2> {
+        GridPositions::column_major(self.top_left, self.grid_size())
+    }
+
+    /// Spirals outwards from the center, see `SpiralPositions`
+    #[inline]
+    pub fn iter_positions_spiral(&self) -> SpiralPositions<IVec2> {
+        SpiralPositions::new(self.top_left, self.grid_size())
+    }
+
+    /// Lazy version of `border_positions()`
+    #[inline]
+    pub fn iter_border_positions(&self) -> BorderPositions<IVec2> {
+        BorderPositions::new(self.top_left, self.grid_size())
+    }
+
+    /// Size, or zero if the corners are the wrong way round
+    #[inline]
+    fn grid_size(&self) -> UVec2 {
+        (self.bottom_right - self.top_left)
+            .max(IVec2::ZERO)
+            .as_uvec2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_set_operations() {
+        let a = Rect::<u32>::from_corners(UVec2::new(0, 0), UVec2::new(4, 4));
+        let b = Rect::<u32>::from_corners(UVec2::new(2, 1), UVec2::new(6, 3));
+        let touching = Rect::<u32>::from_corners(UVec2::new(4, 0), UVec2::new(6, 4));
+
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&touching));
+        assert_eq!(a.intersection(&touching), None);
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::from_corners(UVec2::new(2, 1), UVec2::new(4, 3)))
+        );
 
-        for y in self.top_left.y..=self.bottom_right.y {
-            for x in self.top_left.x..=self.bottom_right.x {
-                positions.push(IVec2::new(x, y));
+        assert_eq!(
+            a.union(&b),
+            Rect::from_corners(UVec2::ZERO, UVec2::new(6, 4))
+        );
+        assert!(a.union(&b).contains_rect(&a));
+        assert!(!a.contains_rect(&b));
+
+        // Top, bottom and left pieces, b covers the right side
+        let pieces = a.subtract(&b);
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(
+            pieces.iter().map(|piece| piece.area()).sum::<u32>(),
+            a.area() - a.intersection(&b).unwrap().area()
+        );
+        assert!(
+            pieces
+                .iter()
+                .tuple_combinations()
+                .all(|(p, q)| !p.overlaps(q))
+        );
+
+        let inner = Rect::<i32>::from_corners(IVec2::new(1, 1), IVec2::new(3, 3));
+        let outer = Rect::<i32>::from_corners(IVec2::ZERO, IVec2::splat(4));
+        assert_eq!(outer.subtract(&inner).len(), 4);
+        assert!(inner.subtract(&outer).is_empty());
+
+        let flat = Rect::<i32>::from_corners(IVec2::new(2, 2), IVec2::new(2, 5));
+        assert!(flat.subtract(&inner).is_empty());
+        assert!(
+            flat.subtract(&Rect::from_corners(IVec2::splat(8), IVec2::splat(9)))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_half_open_and_closed_rects() {
+        for rect in [
+            Rect::<u32>::from_corners(UVec2::new(1, 2), UVec2::new(5, 5)),
+            Rect::<u32>::from_corners(UVec2::new(3, 3), UVec2::new(4, 6)),
+            Rect::<u32>::from_corners(UVec2::new(2, 2), UVec2::new(2, 4)),
+        ] {
+            let positions = rect.positions();
+
+            assert_eq!(positions.len() as u32, rect.area());
+            assert!(positions.iter().all(|&pos| rect.contains_pos(pos)));
+            assert!(!rect.contains_pos(rect.bottom_right));
+
+            let border = rect.border_positions();
+            assert!(border.iter().all(|pos| positions.contains(pos)));
+            let inner = rect.size().saturating_sub(UVec2::splat(2));
+            assert_eq!(border.len() as u32, rect.area() - inner.element_product());
+
+            match rect.to_closed() {
+                Some(closed) => {
+                    assert_eq!(closed.area(), rect.area());
+                    assert_eq!(closed.positions(), positions);
+                    assert_eq!(closed.border_positions(), border);
+                    assert!(closed.contains_pos(closed.bottom_right));
+                    assert_eq!(Rect::from(closed), rect);
+                }
+                None => {
+                    assert!(rect.is_empty());
+                    assert!(positions.is_empty() && border.is_empty());
+                    assert_eq!(ClosedRect::try_from(rect), Err(RectError::Empty));
+                }
             }
         }
 
-        positions
+        let cell = ClosedRect::<i32>::from_corners(IVec2::splat(-1), IVec2::splat(-1));
+        assert_eq!(cell.size(), IVec2::ONE);
+        assert_eq!(cell.positions(), vec![IVec2::splat(-1)]);
+        assert_eq!(ClosedRect::try_from(cell.to_half_open()), Ok(cell));
+
+        // Inverted Rects have no positions, even inclusive ones
+        let inverted = Rect::<i32> {
+            top_left: IVec2::new(3, 0),
+            bottom_right: IVec2::new(1, 2),
+        };
+        assert!(inverted.positions_inclusive().is_empty());
+        let inverted = Rect::<u32> {
+            top_left: UVec2::new(0, 3),
+            bottom_right: UVec2::new(2, 1),
+        };
+        assert!(inverted.positions_inclusive().is_empty());
+        assert_eq!(
+            Rect::<u32>::from_corners(UVec2::new(1, 1), UVec2::new(2, 1)).positions_inclusive(),
+            vec![UVec2::new(1, 1), UVec2::new(2, 1)]
+        );
     }
 
-    /// TODO - improve performance
-    #[inline]
-    pub fn border_positions(&self) -> Vec<IVec2> {
-        let valid_xs = [self.top_left.x, self.bottom_right.x];
-        let valid_ys = [self.top_left.y, self.bottom_right.y];
+    #[test]
+    fn test_rect_scalar_conversions() {
+        let rect = Rect::<f64>::from_corners(DVec2::new(-1.5, 0.25), DVec2::new(2.5, 3.75));
+
+        assert_eq!(
+            rect.convert::<i64>(Rounding::Floor),
+            Rect::from_corners(I64Vec2::new(-2, 0), I64Vec2::new(2, 3))
+        );
+        assert_eq!(
+            rect.convert::<i64>(Rounding::Ceil),
+            Rect::from_corners(I64Vec2::new(-1, 1), I64Vec2::new(3, 4))
+        );
+        assert_eq!(
+            rect.convert::<i64>(Rounding::Round),
+            Rect::from_corners(I64Vec2::new(-2, 0), I64Vec2::new(3, 4))
+        );
+        assert_eq!(
+            rect.convert::<i32>(Rounding::Outward),
+            Rect::from_corners(IVec2::new(-2, 0), IVec2::new(3, 4))
+        );
+
+        assert_eq!(rect.try_cast::<i32>(), None);
+        assert_eq!(rect.cast::<i32>().top_left, IVec2::new(-1, 0));
+
+        let tiles = Rect::<u16>::from_corners(U16Vec2::new(2, 3), U16Vec2::new(10, 7));
+        assert_eq!(tiles.area(), 32);
+        assert_eq!(
+            tiles.try_cast::<u32>(),
+            Some(Rect::from_corners(UVec2::new(2, 3), UVec2::new(10, 7)))
+        );
+        assert_eq!(
+            Rect::<i32>::from_corners(IVec2::new(-1, 0), IVec2::ONE).try_cast::<u16>(),
+            None
+        );
+
+        // Beyond 2^53, where f64 can't hold every integer
+        let big = (1_i64 << 53) + 1;
+        let huge = Rect::<i64>::from_corners(I64Vec2::new(big, -big), I64Vec2::new(big + 2, 0));
+
+        assert_eq!(huge.cast::<i64>(), huge);
+        assert_eq!(huge.try_cast::<i64>(), Some(huge));
+        assert_eq!(huge.convert::<i64>(Rounding::Round), huge);
+        assert_eq!(huge.try_cast::<f64>(), None);
+        assert_eq!(huge.try_cast::<i32>(), None);
+        assert_eq!(huge.cast::<i32>().bottom_right, IVec2::new(i32::MAX, 0));
+        assert_eq!(
+            Rect::<i64>::from_corners(I64Vec2::new(1 << 60, 0), I64Vec2::new(1 << 61, 4))
+                .try_cast::<f64>(),
+            Some(Rect::from_corners(
+                DVec2::new((1_i64 << 60) as f64, 0.0),
+                DVec2::new((1_i64 << 61) as f64, 4.0)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_rect_point_queries() {
+        let rect = Rect::<f32>::from_corners(Vec2::ZERO, Vec2::new(10.0, 4.0));
+
+        assert_eq!(
+            rect.clamp_point(Vec2::new(12.0, -3.0)),
+            Vec2::new(10.0, 0.0)
+        );
+        assert_eq!(rect.distance_to_point(Vec2::new(13.0, 8.0)), 5.0);
+        assert_eq!(rect.distance_to_point(Vec2::new(5.0, 2.0)), 0.0);
+        assert_eq!(rect.closest_point(Vec2::new(5.0, 3.0)), Vec2::new(5.0, 4.0));
+
+        let rect = Rect::<u32>::from_corners(UVec2::new(2, 2), UVec2::new(5, 5));
+
+        assert_eq!(rect.clamp_point(UVec2::ZERO), UVec2::new(2, 2));
+        assert_eq!(rect.distance_to_point(UVec2::new(2, 0)), 2.0);
+        // Integer Rects clamp onto the last row & column of `positions()`
+        assert_eq!(rect.clamp_point(UVec2::new(9, 3)), UVec2::new(4, 3));
+        assert_eq!(rect.distance_to_point(UVec2::new(7, 4)), 3.0);
+        assert!(rect.contains_pos(rect.clamp_point(UVec2::splat(100))));
+
+        // Already on the border
+        assert_eq!(rect.closest_point(UVec2::new(4, 3)), UVec2::new(4, 3));
+        // Equally close to every edge
+        assert_eq!(rect.closest_point(UVec2::new(3, 3)), UVec2::new(2, 3));
+        assert!(
+            rect.border_positions()
+                .contains(&rect.closest_point(UVec2::new(3, 3)))
+        );
 
-        self.positions_inclusive()
-            .into_iter()
-            .filter(|&pos| valid_xs.contains(&pos.x) || valid_ys.contains(&pos.y))
-            .collect()
+        let empty = Rect::<u32>::from_corners(UVec2::ZERO, UVec2::new(0, 4));
+        assert_eq!(empty.clamp_point(UVec2::new(5, 2)), UVec2::new(0, 2));
     }
 }
@@ -0,0 +1,447 @@
+use crate::*;
+
+/// An amount for each side of a Rect, used as padding (`Rect::inset`) or margins (`Rect::outset`)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Margins<T: RectScalar> {
+    pub left: T,
+    pub right: T,
+    pub top: T,
+    pub bottom: T,
+}
+
+impl<T: RectScalar> Margins<T> {
+    pub fn new(left: T, right: T, top: T, bottom: T) -> Self {
+        Self {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    /// The same amount on every side
+    pub fn all(amount: T) -> Self {
+        Self::new(amount, amount, amount, amount)
+    }
+
+    /// `horizontal` on the left & right, `vertical` on the top & bottom
+    pub fn symmetric(horizontal: T, vertical: T) -> Self {
+        Self::new(horizontal, horizontal, vertical, vertical)
+    }
+}
+
+/// One of the nine points a child Rect can be aligned to inside a parent
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Row-major
+    pub const ALL: [Anchor; 9] = [
+        Anchor::TopLeft,
+        Anchor::Top,
+        Anchor::TopRight,
+        Anchor::Left,
+        Anchor::Center,
+        Anchor::Right,
+        Anchor::BottomLeft,
+        Anchor::Bottom,
+        Anchor::BottomRight,
+    ];
+}
+
+impl From<Dir> for Anchor {
+    /// The edge or corner the direction points towards
+    fn from(dir: Dir) -> Self {
+        match dir {
+            Dir::N => Anchor::Top,
+            Dir::NE => Anchor::TopRight,
+            Dir::E => Anchor::Right,
+            Dir::SE => Anchor::BottomRight,
+            Dir::S => Anchor::Bottom,
+            Dir::SW => Anchor::BottomLeft,
+            Dir::W => Anchor::Left,
+            Dir::NW => Anchor::TopLeft,
+        }
+    }
+}
+
+/// Rect-cut layout
+///
+/// Cuts take a slice off one side of Self, shrinking it in place, and return the slice.
+/// Amounts are clamped to `0..=length`, so larger amounts leave Self empty and negative amounts cut nothing
+impl<T: RectScalar> Rect<T> {
+    /// Removes and returns the leftmost `amount` columns
+    pub fn cut_left(&mut self, amount: T) -> Self {
+        let amount = clamp_amount(amount, self.width());
+        let mut slice = *self;
+
+        slice.shrink_in_dir(Dir::W, self.width() - amount);
+        self.shrink_in_dir(Dir::E, amount);
+
+        slice
+    }
+
+    /// Removes and returns the rightmost `amount` columns
+    pub fn cut_right(&mut self, amount: T) -> Self {
+        let amount = clamp_amount(amount, self.width());
+        let mut slice = *self;
+
+        slice.shrink_in_dir(Dir::E, self.width() - amount);
+        self.shrink_in_dir(Dir::W, amount);
+
+        slice
+    }
+
+    /// Removes and returns the top `amount` rows
+    pub fn cut_top(&mut self, amount: T) -> Self {
+        let amount = clamp_amount(amount, self.height());
+        let mut slice = *self;
+
+        slice.shrink_in_dir(Dir::N, self.height() - amount);
+        self.shrink_in_dir(Dir::S, amount);
+
+        slice
+    }
+
+    /// Removes and returns the bottom `amount` rows
+    pub fn cut_bottom(&mut self, amount: T) -> Self {
+        let amount = clamp_amount(amount, self.height());
+        let mut slice = *self;
+
+        slice.shrink_in_dir(Dir::S, self.height() - amount);
+        self.shrink_in_dir(Dir::N, amount);
+
+        slice
+    }
+
+    /// Splits Self in two, the first half taking `ratio` of the length
+    ///
+    /// `Vertical` splits with a vertical line (left & right halves), `Horizontal` with a horizontal line (top & bottom halves), matching `bisect_at`.
+    /// Unlike `bisect_at` either half can be empty, `ratio` is clamped to 0..=1
+    ///
+    /// Errors if `orientation` is diagonal
+    pub fn split_by_ratio(
+        &self,
+        orientation: Orientation,
+        ratio: f32,
+    ) -> Result<(Self, Self), RectError> {
+        let mut second = *self;
+
+        let first = match orientation {
+            Orientation::Vertical => {
+                second.cut_left(T::from_f32(self.width().to_f32() * ratio.clamp(0.0, 1.0)))
+            }
+            Orientation::Horizontal => {
+                second.cut_top(T::from_f32(self.height().to_f32() * ratio.clamp(0.0, 1.0)))
+            }
+            _ => return Err(RectError::DiagonalOrientation(orientation)),
+        };
+
+        Ok((first, second))
+    }
+
+    /// Splits Self into `count` equal pieces, left-to-right for `Vertical` or top-to-bottom for `Horizontal`
+    ///
+    /// For integer Rects the leftover length is spread out, so piece lengths differ by at most 1
+    ///
+    /// Errors if `orientation` is diagonal
+    pub fn split_evenly(
+        &self,
+        count: u32,
+        orientation: Orientation,
+    ) -> Result<Vec<Self>, RectError> {
+        let pieces = match orientation {
+            Orientation::Vertical => spans(
+                T::v2_x(self.top_left),
+                T::v2_x(self.bottom_right),
+                count,
+                T::zero(),
+            )
+            .into_iter()
+            .map(|(left, right)| Self {
+                top_left: T::v2_new(left, T::v2_y(self.top_left)),
+                bottom_right: T::v2_new(right, T::v2_y(self.bottom_right)),
+            })
+            .collect(),
+            Orientation::Horizontal => spans(
+                T::v2_y(self.top_left),
+                T::v2_y(self.bottom_right),
+                count,
+                T::zero(),
+            )
+            .into_iter()
+            .map(|(top, bottom)| Self {
+                top_left: T::v2_new(T::v2_x(self.top_left), top),
+                bottom_right: T::v2_new(T::v2_x(self.bottom_right), bottom),
+            })
+            .collect(),
+            _ => return Err(RectError::DiagonalOrientation(orientation)),
+        };
+
+        Ok(pieces)
+    }
+
+    /// Splits Self into a grid of `rows` × `columns` cells, with `gap` between neighbouring cells
+    ///
+    /// Cells are in row-major order. If the gaps don't fit, the cells are empty
+    pub fn split_grid(&self, rows: u32, columns: u32, gap: T) -> Vec<Self> {
+        let xs = spans(
+            T::v2_x(self.top_left),
+            T::v2_x(self.bottom_right),
+            columns,
+            gap,
+        );
+        let ys = spans(
+            T::v2_y(self.top_left),
+            T::v2_y(self.bottom_right),
+            rows,
+            gap,
+        );
+
+        ys.iter()
+            .flat_map(|&(top, bottom)| {
+                xs.iter().map(move |&(left, right)| Self {
+                    top_left: T::v2_new(left, top),
+                    bottom_right: T::v2_new(right, bottom),
+                })
+            })
+            .collect()
+    }
+
+    /// Shrinks each side inwards by its padding
+    ///
+    /// Padding is clamped to `0..=length` so Self never turns inside out, left & top are applied first
+    #[must_use]
+    pub fn inset(&self, padding: &Margins<T>) -> Self {
+        let mut rect = *self;
+
+        let left = clamp_amount(padding.left, rect.width());
+        rect.shrink_in_dir(Dir::E, left);
+        let right = clamp_amount(padding.right, rect.width());
+        rect.shrink_in_dir(Dir::W, right);
+
+        let top = clamp_amount(padding.top, rect.height());
+        rect.shrink_in_dir(Dir::S, top);
+        let bottom = clamp_amount(padding.bottom, rect.height());
+        rect.shrink_in_dir(Dir::N, bottom);
+
+        rect
+    }
+
+    /// Grows each side outwards by its margin
+    ///
+    /// Negative margins are treated as 0. Sides saturate at the scalar's limits instead of overflowing,
+    /// eg. a `Rect<u32>` touching the origin can't grow left or up
+    #[must_use]
+    pub fn outset(&self, margins: &Margins<T>) -> Self {
+        let grow_down = |value: T, amount: T| {
+            let amount = max_scalar(amount, T::zero());
+
+            if value < T::min() + amount {
+                T::min()
+            } else {
+                value - amount
+            }
+        };
+        let grow_up = |value: T, amount: T| {
+            let amount = max_scalar(amount, T::zero());
+
+            if value > T::max() - amount {
+                T::max()
+            } else {
+                value + amount
+            }
+        };
+
+        Self {
+            top_left: T::v2_new(
+                grow_down(T::v2_x(self.top_left), margins.left),
+                grow_down(T::v2_y(self.top_left), margins.top),
+            ),
+            bottom_right: T::v2_new(
+                grow_up(T::v2_x(self.bottom_right), margins.right),
+                grow_up(T::v2_y(self.bottom_right), margins.bottom),
+            ),
+        }
+    }
+
+    /// A Rect of `size` aligned to `anchor` inside Self
+    ///
+    /// `size` is clamped to fit inside Self. Centering rounds towards the top left for integer Rects
+    pub fn anchored(&self, size: T::V2, anchor: Anchor) -> Self {
+        let size = T::v2_new(
+            min_scalar(T::v2_x(size), self.width()),
+            min_scalar(T::v2_y(size), self.height()),
+        );
+        let free = self.size() - size;
+
+        let (fx, fy) = match anchor {
+            Anchor::TopLeft => (T::zero(), T::zero()),
+            Anchor::Top => (T::v2_x(free) / T::two(), T::zero()),
+            Anchor::TopRight => (T::v2_x(free), T::zero()),
+            Anchor::Left => (T::zero(), T::v2_y(free) / T::two()),
+            Anchor::Center => (T::v2_x(free) / T::two(), T::v2_y(free) / T::two()),
+            Anchor::Right => (T::v2_x(free), T::v2_y(free) / T::two()),
+            Anchor::BottomLeft => (T::zero(), T::v2_y(free)),
+            Anchor::Bottom => (T::v2_x(free) / T::two(), T::v2_y(free)),
+            Anchor::BottomRight => (T::v2_x(free), T::v2_y(free)),
+        };
+
+        Self::from_top_left_and_size(self.top_left + T::v2_new(fx, fy), size)
+    }
+}
+
+/// `count` spans from `start` to `end` with `gap` between each, as `(start, end)` pairs
+fn spans<T: RectScalar>(start: T, end: T, count: u32, gap: T) -> Vec<(T, T)> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let length = end - start;
+    let total_gap = gap * T::from_f64((count - 1) as f64);
+    let available = if total_gap < length {
+        length - total_gap
+    } else {
+        T::zero()
+    };
+
+    // Span boundaries within the available length, the last is exact to avoid rounding errors
+    let boundary = |idx: u32| {
+        if idx == count {
+            available
+        } else {
+            T::from_f64(available.to_f64() * idx as f64 / count as f64)
+        }
+    };
+
+    (0..count)
+        .map(|idx| {
+            let offset = start + gap * T::from_f64(idx as f64);
+
+            (
+                min_scalar(offset + boundary(idx), end),
+                min_scalar(offset + boundary(idx + 1), end),
+            )
+        })
+        .collect()
+}
+
+/// Clamps `amount` to `0..=length`, NaN becomes 0
+#[inline]
+fn clamp_amount<T: RectScalar>(amount: T, length: T) -> T {
+    max_scalar(T::zero(), min_scalar(amount, length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_cut() {
+        let mut rect = Rect::<u32>::from_corners(UVec2::ZERO, UVec2::new(20, 10));
+
+        let header = rect.cut_top(2);
+        let sidebar = rect.cut_left(5);
+        let footer = rect.cut_bottom(1);
+        let overflow = rect.cut_right(100);
+
+        assert_eq!(header, Rect::from_corners(UVec2::ZERO, UVec2::new(20, 2)));
+        assert_eq!(
+            sidebar,
+            Rect::from_corners(UVec2::new(0, 2), UVec2::new(5, 10))
+        );
+        assert_eq!(
+            footer,
+            Rect::from_corners(UVec2::new(5, 9), UVec2::new(20, 10))
+        );
+        assert_eq!(
+            overflow,
+            Rect::from_corners(UVec2::new(5, 2), UVec2::new(20, 9))
+        );
+        assert!(rect.is_empty());
+
+        // Negative amounts cut nothing instead of growing the slice
+        let mut rect = Rect::<i32>::from_corners(IVec2::ZERO, IVec2::new(10, 10));
+        let slice = rect.cut_left(-3);
+        assert!(slice.is_empty());
+        assert_eq!(slice.top_left, IVec2::ZERO);
+        assert_eq!(rect.cut_bottom(-2).height(), 0);
+        assert_eq!(rect, Rect::from_corners(IVec2::ZERO, IVec2::new(10, 10)));
+    }
+
+    #[test]
+    fn test_rect_split() {
+        let rect = Rect::<u32>::from_corners(UVec2::ZERO, UVec2::new(10, 4));
+
+        let (left, right) = rect.split_by_ratio(Orientation::Vertical, 0.3).unwrap();
+        assert_eq!(left.width(), 3);
+        assert_eq!(right.width(), 7);
+        assert!(rect.split_by_ratio(Orientation::DiagNE, 0.5).is_err());
+
+        let columns = rect.split_evenly(3, Orientation::Vertical).unwrap();
+        assert_eq!(
+            columns.iter().map(|c| c.width()).collect_vec(),
+            vec![3, 3, 4]
+        );
+        assert_eq!(columns[2].bottom_right, rect.bottom_right);
+
+        let cells = rect.split_grid(2, 3, 1);
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0], Rect::from_corners(UVec2::ZERO, UVec2::new(2, 1)));
+        assert_eq!(cells[5].bottom_right, rect.bottom_right);
+        assert!(
+            cells
+                .iter()
+                .tuple_combinations()
+                .all(|(a, b)| !a.overlaps(b))
+        );
+    }
+
+    #[test]
+    fn test_rect_margins_and_anchors() {
+        let rect = Rect::<i32>::from_corners(IVec2::ZERO, IVec2::new(10, 8));
+
+        let inner = rect.inset(&Margins::new(1, 2, 3, 4));
+        assert_eq!(
+            inner,
+            Rect::from_corners(IVec2::new(1, 3), IVec2::new(8, 4))
+        );
+        assert_eq!(inner.outset(&Margins::new(1, 2, 3, 4)), rect);
+        assert!(rect.inset(&Margins::all(6)).is_empty());
+        assert_eq!(rect.inset(&Margins::all(-2)), rect);
+        assert_eq!(rect.outset(&Margins::all(-2)), rect);
+
+        // Saturates at 0 instead of underflowing
+        let origin = Rect::<u32>::from_corners(UVec2::ZERO, UVec2::new(4, 4));
+        assert_eq!(
+            origin.outset(&Margins::all(2)),
+            Rect::from_corners(UVec2::ZERO, UVec2::new(6, 6))
+        );
+        assert_eq!(
+            Rect::<u16>::from_corners(U16Vec2::new(1, 1), U16Vec2::splat(u16::MAX - 1))
+                .outset(&Margins::all(3)),
+            Rect::from_corners(U16Vec2::ZERO, U16Vec2::splat(u16::MAX))
+        );
+
+        let size = IVec2::new(4, 2);
+        assert_eq!(
+            rect.anchored(size, Anchor::Center),
+            Rect::from_corners(IVec2::new(3, 3), IVec2::new(7, 5))
+        );
+        assert_eq!(
+            rect.anchored(size, Anchor::from(Dir::SE)).bottom_right,
+            rect.bottom_right
+        );
+        assert_eq!(rect.anchored(IVec2::splat(20), Anchor::Top), rect);
+    }
+}
@@ -38,6 +38,11 @@ pub trait RectScalar:
         + Mul<Self, Output = Self::V2>
         + MulAssign;
 
+    /// True for scalars with whole-number steps, which must also implement `IntegerScalar`
+    ///
+    /// Integer Rects cover cells, so this decides where the point queries and geometry tests end
+    const IS_INTEGER: bool;
+
     // type RangeInc: Iterator<Item = Self>;
     // type RangeEx: Iterator<Item = Self>;
 
@@ -48,6 +53,36 @@ pub trait RectScalar:
     fn min() -> Self;
     fn to_f32(self) -> f32;
     fn from_f32(float: f32) -> Self;
+    /// Exact for every scalar except `i64` beyond ±2^53
+    ///
+    /// Defaults to going through `to_f32`, override for more precision
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self.to_f32() as f64
+    }
+
+    /// Saturates, and truncates towards zero for integers
+    ///
+    /// Defaults to going through `from_f32`, override for more precision
+    #[inline]
+    fn from_f64(float: f64) -> Self {
+        Self::from_f32(float as f32)
+    }
+
+    /// The exact value for integer scalars, None for floats
+    ///
+    /// Lets integer conversions skip floats entirely. Defaults to None, so conversions go through `to_f64`
+    #[inline]
+    fn to_i128(self) -> Option<i128> {
+        None
+    }
+
+    /// None if `int` is out of range, or the scalar isn't an integer
+    #[inline]
+    fn from_i128(int: i128) -> Option<Self> {
+        let _ = int;
+        None
+    }
 
     // fn range_inclusive(start: Self, end: Self) -> Self::RangeInc;
     // fn range_exclusive(start: Self, end: Self) -> Self::RangeEx;
@@ -61,11 +96,29 @@ pub trait RectScalar:
     fn v2_min_element(v: Self::V2) -> Self;
     fn v2_max_element(v: Self::V2) -> Self;
     fn v2_element_product(v: Self::V2) -> Self;
+
+    #[inline]
+    fn v2_to_dvec2(v: Self::V2) -> DVec2 {
+        DVec2::new(Self::v2_x(v).to_f64(), Self::v2_y(v).to_f64())
+    }
 }
 
+/// Marker for scalars with whole-number steps, where `one()` is the distance between neighbouring cells
+///
+/// Required by types like `ClosedRect` that count cells, and would give meaningless sizes with floats.
+/// Implementors must set `RectScalar::IS_INTEGER`
+pub trait IntegerScalar: RectScalar {}
+
+impl IntegerScalar for i32 {}
+impl IntegerScalar for u32 {}
+impl IntegerScalar for i64 {}
+impl IntegerScalar for u16 {}
+
 impl RectScalar for i32 {
     type V2 = IVec2;
 
+    const IS_INTEGER: bool = true;
+
     // type RangeInc = RangeInclusive<i32>;
 
     // type RangeEx = Range<i32>;
@@ -98,6 +151,22 @@ impl RectScalar for i32 {
         float as i32
     }
 
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(float: f64) -> Self {
+        float as i32
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        Some(self as i128)
+    }
+
+    fn from_i128(int: i128) -> Option<Self> {
+        Self::try_from(int).ok()
+    }
+
     // fn range_inclusive(start: Self, end: Self) -> Self::RangeInc {
     //     start..=end
     // }
@@ -142,6 +211,8 @@ impl RectScalar for i32 {
 impl RectScalar for u32 {
     type V2 = UVec2;
 
+    const IS_INTEGER: bool = true;
+
     // type RangeInc = RangeInclusive<u32>;
 
     // type RangeEx = Range<u32>;
@@ -174,6 +245,22 @@ impl RectScalar for u32 {
         float as u32
     }
 
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(float: f64) -> Self {
+        float as u32
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        Some(self as i128)
+    }
+
+    fn from_i128(int: i128) -> Option<Self> {
+        Self::try_from(int).ok()
+    }
+
     // fn range_inclusive(start: Self, end: Self) -> Self::RangeInc {
     //     start..=end
     // }
@@ -218,6 +305,8 @@ impl RectScalar for u32 {
 impl RectScalar for f32 {
     type V2 = Vec2;
 
+    const IS_INTEGER: bool = false;
+
     // type RangeInc = RangeInclusive<f32>;
 
     // type RangeEx = Range<f32>;
@@ -250,6 +339,14 @@ impl RectScalar for f32 {
         float
     }
 
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(float: f64) -> Self {
+        float as f32
+    }
+
     // fn range_inclusive(start: Self, end: Self) -> Self::RangeInc {
     //     start..=end
     // }
@@ -290,3 +387,241 @@ impl RectScalar for f32 {
         v.element_product()
     }
 }
+
+impl RectScalar for f64 {
+    type V2 = DVec2;
+
+    const IS_INTEGER: bool = false;
+
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn one() -> Self {
+        1.0
+    }
+
+    fn two() -> Self {
+        2.0
+    }
+
+    fn max() -> Self {
+        f64::MAX
+    }
+
+    fn min() -> Self {
+        f64::MIN
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(float: f32) -> Self {
+        float as f64
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn from_f64(float: f64) -> Self {
+        float
+    }
+
+    fn v2_new(x: Self, y: Self) -> Self::V2 {
+        DVec2::new(x, y)
+    }
+
+    fn v2_x(v: Self::V2) -> Self {
+        v.x
+    }
+
+    fn v2_y(v: Self::V2) -> Self {
+        v.y
+    }
+
+    fn v2_x_mut(v: &mut Self::V2) -> &mut Self {
+        &mut v.x
+    }
+
+    fn v2_y_mut(v: &mut Self::V2) -> &mut Self {
+        &mut v.y
+    }
+
+    fn v2_min_element(v: Self::V2) -> Self {
+        v.min_element()
+    }
+
+    fn v2_max_element(v: Self::V2) -> Self {
+        v.max_element()
+    }
+
+    fn v2_element_product(v: Self::V2) -> Self {
+        v.element_product()
+    }
+}
+
+impl RectScalar for i64 {
+    type V2 = I64Vec2;
+
+    const IS_INTEGER: bool = true;
+
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn two() -> Self {
+        2
+    }
+
+    fn max() -> Self {
+        i64::MAX
+    }
+
+    fn min() -> Self {
+        i64::MIN
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(float: f32) -> Self {
+        float as i64
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(float: f64) -> Self {
+        float as i64
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        Some(self as i128)
+    }
+
+    fn from_i128(int: i128) -> Option<Self> {
+        Self::try_from(int).ok()
+    }
+
+    fn v2_new(x: Self, y: Self) -> Self::V2 {
+        I64Vec2::new(x, y)
+    }
+
+    fn v2_x(v: Self::V2) -> Self {
+        v.x
+    }
+
+    fn v2_y(v: Self::V2) -> Self {
+        v.y
+    }
+
+    fn v2_x_mut(v: &mut Self::V2) -> &mut Self {
+        &mut v.x
+    }
+
+    fn v2_y_mut(v: &mut Self::V2) -> &mut Self {
+        &mut v.y
+    }
+
+    fn v2_min_element(v: Self::V2) -> Self {
+        v.min_element()
+    }
+
+    fn v2_max_element(v: Self::V2) -> Self {
+        v.max_element()
+    }
+
+    fn v2_element_product(v: Self::V2) -> Self {
+        v.element_product()
+    }
+}
+
+impl RectScalar for u16 {
+    type V2 = U16Vec2;
+
+    const IS_INTEGER: bool = true;
+
+    fn zero() -> Self {
+        0
+    }
+
+    fn one() -> Self {
+        1
+    }
+
+    fn two() -> Self {
+        2
+    }
+
+    fn max() -> Self {
+        u16::MAX
+    }
+
+    fn min() -> Self {
+        u16::MIN
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(float: f32) -> Self {
+        float as u16
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(float: f64) -> Self {
+        float as u16
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        Some(self as i128)
+    }
+
+    fn from_i128(int: i128) -> Option<Self> {
+        Self::try_from(int).ok()
+    }
+
+    fn v2_new(x: Self, y: Self) -> Self::V2 {
+        U16Vec2::new(x, y)
+    }
+
+    fn v2_x(v: Self::V2) -> Self {
+        v.x
+    }
+
+    fn v2_y(v: Self::V2) -> Self {
+        v.y
+    }
+
+    fn v2_x_mut(v: &mut Self::V2) -> &mut Self {
+        &mut v.x
+    }
+
+    fn v2_y_mut(v: &mut Self::V2) -> &mut Self {
+        &mut v.y
+    }
+
+    fn v2_min_element(v: Self::V2) -> Self {
+        v.min_element()
+    }
+
+    fn v2_max_element(v: Self::V2) -> Self {
+        v.max_element()
+    }
+
+    fn v2_element_product(v: Self::V2) -> Self {
+        v.element_product()
+    }
+}
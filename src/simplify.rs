@@ -0,0 +1,284 @@
+use crate::*;
+
+use core::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// A trait for reducing the number of points in a polyline
+///
+/// The first and last points of a polyline are always kept
+pub trait Simplify {
+    type V;
+
+    /// Ramer-Douglas-Peucker simplification
+    ///
+    /// Removes points that are closer than `epsilon` to the line between the points that are kept
+    fn simplify_rdp(&self, epsilon: f32) -> Vec<Self::V>;
+
+    /// Visvalingam-Whyatt simplification
+    ///
+    /// Repeatedly removes the point forming the smallest triangle with its neighbours, until every triangle has an area of at least `min_area`
+    fn simplify_visvalingam(&self, min_area: f32) -> Vec<Self::V>;
+
+    /// Removes points lying on a straight line between their neighbours
+    ///
+    /// If `closed` is true, the polyline is treated as a loop and the first and last points may also be removed
+    fn merge_collinear(&self, closed: bool) -> Vec<Self::V>;
+}
+
+impl Simplify for [Vec2] {
+    type V = Vec2;
+
+    fn simplify_rdp(&self, epsilon: f32) -> Vec<Self::V> {
+        rdp_kept(self, epsilon).map(|i| self[i]).collect()
+    }
+
+    fn simplify_visvalingam(&self, min_area: f32) -> Vec<Self::V> {
+        visvalingam_kept(self, min_area).map(|i| self[i]).collect()
+    }
+
+    fn merge_collinear(&self, closed: bool) -> Vec<Self::V> {
+        collinear_kept(self, closed).map(|i| self[i]).collect()
+    }
+}
+
+impl Simplify for [IVec2] {
+    type V = IVec2;
+
+    fn simplify_rdp(&self, epsilon: f32) -> Vec<Self::V> {
+        let points = self.iter().map(|p| p.as_vec2()).collect_vec();
+
+        rdp_kept(&points, epsilon).map(|i| self[i]).collect()
+    }
+
+    fn simplify_visvalingam(&self, min_area: f32) -> Vec<Self::V> {
+        let points = self.iter().map(|p| p.as_vec2()).collect_vec();
+
+        visvalingam_kept(&points, min_area)
+            .map(|i| self[i])
+            .collect()
+    }
+
+    fn merge_collinear(&self, closed: bool) -> Vec<Self::V> {
+        let points = self.iter().map(|p| p.as_vec2()).collect_vec();
+
+        collinear_kept(&points, closed).map(|i| self[i]).collect()
+    }
+}
+
+/// Distance from `point` to the line segment `a` -> `b`
+#[inline]
+fn distance_to_segment(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_squared();
+
+    if length_sq == 0.0 {
+        return point.distance(a);
+    }
+
+    let t = ((point - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+
+    point.distance(a + ab * t)
+}
+
+#[inline]
+fn triangle_area(a: Vec2, b: Vec2, c: Vec2) -> f32 {
+    (b - a).perp_dot(c - a).abs() * 0.5
+}
+
+/// Indices of the points kept by Ramer-Douglas-Peucker
+fn rdp_kept(points: &[Vec2], epsilon: f32) -> impl Iterator<Item = usize> {
+    let mut keep = vec![false; points.len()];
+
+    if points.len() > 2 {
+        keep[0] = true;
+        keep[points.len() - 1] = true;
+
+        // Iterative to avoid deep recursion on long polylines
+        let mut stack = vec![(0, points.len() - 1)];
+
+        while let Some((start, end)) = stack.pop() {
+            let mut max_dist = 0.0;
+            let mut max_idx = start;
+
+            for i in start + 1..end {
+                let dist = distance_to_segment(points[i], points[start], points[end]);
+
+                if dist > max_dist {
+                    max_dist = dist;
+                    max_idx = i;
+                }
+            }
+
+            if max_dist > epsilon {
+                keep[max_idx] = true;
+                stack.push((start, max_idx));
+                stack.push((max_idx, end));
+            }
+        }
+    } else {
+        keep.fill(true);
+    }
+
+    keep.into_iter()
+        .enumerate()
+        .filter_map(|(i, kept)| kept.then_some(i))
+}
+
+/// Indices of the points kept by Visvalingam-Whyatt
+fn visvalingam_kept(points: &[Vec2], min_area: f32) -> impl Iterator<Item = usize> {
+    let len = points.len();
+    let mut keep = vec![true; len];
+
+    if len > 2 {
+        // Doubly linked list over the remaining points
+        let mut prev = (0..len).map(|i| i.wrapping_sub(1)).collect_vec();
+        let mut next = (1..=len).collect_vec();
+
+        let area_at = |i: usize, prev: &[usize], next: &[usize]| {
+            triangle_area(points[prev[i]], points[i], points[next[i]])
+        };
+
+        // Endpoints can never be removed
+        let mut areas = (0..len)
+            .map(|i| {
+                if i == 0 || i == len - 1 {
+                    f32::INFINITY
+                } else {
+                    area_at(i, &prev, &next)
+                }
+            })
+            .collect_vec();
+        let mut heap: BinaryHeap<_> = (1..len - 1)
+            .map(|i| Reverse(AreaEntry(areas[i], i)))
+            .collect();
+
+        while let Some(Reverse(AreaEntry(area, i))) = heap.pop() {
+            // Skip stale entries left behind after a neighbour was removed
+            if !keep[i] || area != areas[i] {
+                continue;
+            }
+
+            if area >= min_area {
+                break;
+            }
+
+            keep[i] = false;
+
+            let (p, n) = (prev[i], next[i]);
+            next[p] = n;
+            prev[n] = p;
+
+            for neighbour in [p, n] {
+                if neighbour != 0 && neighbour != len - 1 {
+                    areas[neighbour] = area_at(neighbour, &prev, &next);
+                    heap.push(Reverse(AreaEntry(areas[neighbour], neighbour)));
+                }
+            }
+        }
+    }
+
+    keep.into_iter()
+        .enumerate()
+        .filter_map(|(i, kept)| kept.then_some(i))
+}
+
+/// Indices of the points that aren't collinear with their neighbours
+fn collinear_kept(points: &[Vec2], closed: bool) -> impl Iterator<Item = usize> {
+    let len = points.len();
+    // Points where the line doubles back on itself are kept
+    let is_collinear =
+        |a: Vec2, b: Vec2, c: Vec2| (b - a).perp_dot(c - b) == 0.0 && (b - a).dot(c - b) > 0.0;
+
+    let mut kept = Vec::with_capacity(len);
+
+    for i in 0..len {
+        let has_neighbours = closed || (i > 0 && i < len - 1);
+
+        if len > 2 && has_neighbours {
+            let a = points[(i + len - 1) % len];
+            let c = points[(i + 1) % len];
+
+            if is_collinear(a, points[i], c) {
+                continue;
+            }
+        }
+
+        kept.push(i);
+    }
+
+    kept.into_iter()
+}
+
+/// Orders triangle areas for the Visvalingam heap
+#[derive(PartialEq)]
+struct AreaEntry(f32, usize);
+
+impl Eq for AreaEntry {}
+
+impl PartialOrd for AreaEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AreaEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .partial_cmp(&other.0)
+            .unwrap_or(Ordering::Equal)
+            .then(self.1.cmp(&other.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify_rdp() {
+        let line = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.1),
+            Vec2::new(2.0, -0.1),
+            Vec2::new(3.0, 5.0),
+            Vec2::new(4.0, 6.0),
+            Vec2::new(5.0, 7.0),
+        ];
+
+        let simplified = line.simplify_rdp(0.5);
+
+        assert_eq!(
+            simplified,
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, -0.1),
+                Vec2::new(3.0, 5.0),
+                Vec2::new(5.0, 7.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_simplify_visvalingam() {
+        let line = [
+            IVec2::new(0, 0),
+            IVec2::new(1, 1),
+            IVec2::new(2, 0),
+            IVec2::new(3, 10),
+            IVec2::new(4, 0),
+        ];
+
+        // The small bump is removed, the spike stays
+        let simplified = line.simplify_visvalingam(2.0);
+
+        assert_eq!(
+            simplified,
+            vec![
+                IVec2::new(0, 0),
+                IVec2::new(2, 0),
+                IVec2::new(3, 10),
+                IVec2::new(4, 0)
+            ]
+        );
+    }
+}
@@ -47,6 +47,15 @@ pub trait Positions {
     /// );
     /// ```
     fn positions_inclusive(&self) -> Vec<Self::V>;
+
+    /// Lazy version of `positions()`
+    fn iter_positions(&self) -> GridPositions<Self::V>;
+
+    /// Top-to-bottom & left-to-right order
+    fn iter_positions_column_major(&self) -> GridPositions<Self::V>;
+
+    /// Spirals outwards from the center, see `SpiralPositions`
+    fn iter_positions_spiral(&self) -> SpiralPositions<Self::V>;
 }
 
 impl Positions for IVec2 {
@@ -54,28 +63,28 @@ impl Positions for IVec2 {
 
     #[inline]
     fn positions(&self) -> Vec<Self::V> {
-        let mut positions = Vec::new();
-
-        for y in 0..self.y {
-            for x in 0..self.x {
-                positions.push(Self::new(x, y));
-            }
-        }
-
-        positions
+        self.iter_positions().collect()
     }
 
     #[inline]
     fn positions_inclusive(&self) -> Vec<Self::V> {
-        let mut positions = Vec::new();
+        GridPositions::row_major(Self::ZERO, (*self + IVec2::ONE).max(IVec2::ZERO).as_uvec2())
+            .collect()
+    }
 
-        for y in 0..=self.y {
-            for x in 0..=self.x {
-                positions.push(Self::new(x, y));
-            }
-        }
+    #[inline]
+    fn iter_positions(&self) -> GridPositions<Self::V> {
+        GridPositions::row_major(Self::ZERO, self.max(IVec2::ZERO).as_uvec2())
+    }
+
+    #[inline]
+    fn iter_positions_column_major(&self) -> GridPositions<Self::V> {
+        GridPositions::column_major(Self::ZERO, self.max(IVec2::ZERO).as_uvec2())
+    }
 
-        positions
+    #[inline]
+    fn iter_positions_spiral(&self) -> SpiralPositions<Self::V> {
+        SpiralPositions::new(Self::ZERO, self.max(IVec2::ZERO).as_uvec2())
     }
 }
 
@@ -84,28 +93,27 @@ impl Positions for UVec2 {
 
     #[inline]
     fn positions(&self) -> Vec<Self::V> {
-        let mut positions = Vec::new();
-
-        for y in 0..self.y {
-            for x in 0..self.x {
-                positions.push(Self::new(x, y));
-            }
-        }
-
-        positions
+        self.iter_positions().collect()
     }
 
     #[inline]
     fn positions_inclusive(&self) -> Vec<Self::V> {
-        let mut positions = Vec::new();
+        GridPositions::row_major(Self::ZERO, *self + UVec2::ONE).collect()
+    }
 
-        for y in 0..=self.y {
-            for x in 0..=self.x {
-                positions.push(Self::new(x, y));
-            }
-        }
+    #[inline]
+    fn iter_positions(&self) -> GridPositions<Self::V> {
+        GridPositions::row_major(Self::ZERO, *self)
+    }
+
+    #[inline]
+    fn iter_positions_column_major(&self) -> GridPositions<Self::V> {
+        GridPositions::column_major(Self::ZERO, *self)
+    }
 
-        positions
+    #[inline]
+    fn iter_positions_spiral(&self) -> SpiralPositions<Self::V> {
+        SpiralPositions::new(Self::ZERO, *self)
     }
 }
 
@@ -726,6 +734,25 @@ impl Distance for UVec2 {
     }
 }
 
+/// Selects one of the `Distance` measurements at runtime
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DistanceMetric {
+    Euclidian,
+    Manhattan,
+    Chebyshev,
+}
+
+impl DistanceMetric {
+    #[inline(always)]
+    pub fn distance<D: Distance>(self, a: D, b: D::V) -> f32 {
+        match self {
+            Self::Euclidian => a.distance_euclidian(b),
+            Self::Manhattan => a.distance_manhattan(b) as f32,
+            Self::Chebyshev => a.distance_chebyshev(b) as f32,
+        }
+    }
+}
+
 pub trait BoundsCheck {
     type V;
 